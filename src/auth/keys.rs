@@ -0,0 +1,334 @@
+use reqwest::Url;
+use tokio::time::Instant;
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::auth::{sign_url, Auth, REDACTED};
+use crate::query::QueryParams;
+use crate::{Api, GMapsClient, GMapsClientError};
+
+/// Time a key is left unused after being denied or running out of quota
+pub(crate) const DEFAULT_KEY_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// How the requests are spread over the keys of a client holding several
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KeyRotation {
+    /// Every request uses the first available key, the next ones only serve while
+    /// the previous are quarantined
+    #[default]
+    Failover,
+    /// Requests take turns over the available keys
+    RoundRobin,
+}
+
+struct Key {
+    auth: Auth,
+    signing_key: Option<Vec<u8>>,
+    quarantined_until: Mutex<Option<Instant>>,
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Key")
+            .field("auth", &self.auth)
+            .field("signing_key", &self.signing_key.as_ref().map(|_| REDACTED))
+            .field("quarantined_until", &self.quarantined_until)
+            .finish()
+    }
+}
+
+/// Credentials of a client, shared by all of its clones
+/// 
+/// A key answered with REQUEST_DENIED or OVER_QUERY_LIMIT is quarantined for the
+/// cooldown and the request fails over to the next key. When every key is
+/// quarantined the one released first is used anyway, so a single key client
+/// behaves as if there was no quarantine
+#[derive(Debug)]
+pub(crate) struct KeyPool {
+    keys: Vec<Key>,
+    rotation: KeyRotation,
+    cooldown: Duration,
+    next: AtomicUsize,
+}
+
+impl KeyPool {
+
+    /// Creates the pool, decoding the signing secrets of the credentials
+    /// 
+    /// returns: Result<KeyPool, GMapsClientError> failing with InvalidSigningSecret
+    pub(crate) fn new(
+        auths: Vec<Auth>,
+        rotation: KeyRotation,
+        cooldown: Duration,
+    ) -> Result<KeyPool, GMapsClientError> {
+
+        let keys = auths
+            .into_iter()
+            .map(|auth| {
+                Ok(Key {
+                    signing_key: auth.signing_key()?,
+                    auth,
+                    quarantined_until: Mutex::new(None),
+                })
+            })
+            .collect::<Result<Vec<Key>, GMapsClientError>>()?;
+
+        Ok(KeyPool {
+            keys,
+            rotation,
+            cooldown,
+            next: AtomicUsize::new(0),
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn quarantined_until(&self, index: usize) -> Option<Instant> {
+        *self.keys[index].quarantined_until.lock().unwrap()
+    }
+
+    /// Index of the key the next attempt should use
+    pub(crate) fn select(&self) -> usize {
+        let now = Instant::now();
+        let start = match self.rotation {
+            KeyRotation::Failover => 0,
+            KeyRotation::RoundRobin => self.next.fetch_add(1, Ordering::Relaxed) % self.len(),
+        };
+
+        (0..self.len())
+            .map(|offset| (start + offset) % self.len())
+            .find(|&index| self.quarantined_until(index).is_none_or(|until| until <= now))
+            .unwrap_or_else(|| {
+                (0..self.len())
+                    .min_by_key(|&index| self.quarantined_until(index))
+                    .unwrap_or(start)
+            })
+    }
+
+    /// Leaves the key unused for the cooldown
+    pub(crate) fn quarantine(&self, index: usize) {
+        *self.keys[index].quarantined_until.lock().unwrap() = Some(Instant::now() + self.cooldown);
+    }
+
+    /// Builds the url of the base and the parameters, authenticated with the key
+    pub(crate) fn url(&self, index: usize, base: &str, mut params: QueryParams) -> Url {
+        let key = &self.keys[index];
+        key.auth.push_credentials(&mut params);
+
+        let url = params.to_url(base);
+        match &key.signing_key {
+            Some(signing_key) => sign_url(url, signing_key),
+            None => url,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn auths(&self) -> Vec<&Auth> {
+        self.keys.iter().map(|key| &key.auth).collect()
+    }
+}
+
+impl<T> GMapsClient<T> {
+
+    /// Builds the url of an api path authenticated with the given key
+    pub(crate) fn key_url(&self, index: usize, api: Api, path: &str, params: QueryParams) -> Url {
+        self.keys.url(index, &format!("{}/{}", self.base_url(api), path), params)
+    }
+
+    /// Runs a single attempt of a request with the url authenticated by the selected
+    /// key. An attempt denied or out of quota quarantines its key and is repeated with
+    /// the next one, until every key was tried once
+    /// 
+    /// returns: Result<R, GMapsClientError> of the last attempt
+    pub(crate) async fn with_keys<R, F, Fut>(
+        &self,
+        api: Api,
+        path: &str,
+        params: &QueryParams,
+        send: F,
+    ) -> Result<R, GMapsClientError>
+    where
+        F: Fn(Url) -> Fut,
+        Fut: Future<Output = Result<R, GMapsClientError>>,
+    {
+        let mut tried = 0;
+
+        loop {
+            let index = self.keys.select();
+            match send(self.key_url(index, api, path, params.clone())).await {
+                Err(error @ (GMapsClientError::RequestDenied { .. } | GMapsClientError::OverQueryLimit)) => {
+                    self.keys.quarantine(index);
+                    tried += 1;
+                    if tried >= self.keys.len() {
+                        return Err(error);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::{test_builder, RetryPolicy};
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn pool(rotation: KeyRotation) -> KeyPool {
+        let auths = vec![Auth::ApiKey("first".into()), Auth::ApiKey("second".into())];
+        KeyPool::new(auths, rotation, Duration::from_secs(60)).unwrap()
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_failover_prefers_the_first_key() {
+        let pool = pool(KeyRotation::Failover);
+        assert_eq!(pool.select(), 0);
+        assert_eq!(pool.select(), 0);
+
+        pool.quarantine(0);
+        assert_eq!(pool.select(), 1);
+
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert_eq!(pool.select(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_round_robin_skips_quarantined_keys() {
+        let pool = pool(KeyRotation::RoundRobin);
+        assert_eq!(pool.select(), 0);
+        assert_eq!(pool.select(), 1);
+        assert_eq!(pool.select(), 0);
+
+        pool.quarantine(1);
+        assert_eq!(pool.select(), 0);
+        assert_eq!(pool.select(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_all_quarantined_uses_first_released() {
+        let pool = pool(KeyRotation::Failover);
+        pool.quarantine(1);
+        tokio::time::advance(Duration::from_secs(1)).await;
+        pool.quarantine(0);
+
+        assert_eq!(pool.select(), 1);
+    }
+
+    async fn mount(server: &MockServer, key: &str, status: &str, expected: u64) {
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/findplacefromtext/json"))
+            .and(query_param("key", key))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{ "candidates": [], "status": "{}" }}"#,
+                status
+            )))
+            .expect(expected)
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    pub async fn test_validation_quarantines_invalid_keys() {
+        let server = MockServer::start().await;
+        // validation of both keys, then both searches served by the valid one
+        mount(&server, "invalid", "REQUEST_DENIED", 1).await;
+        mount(&server, "valid", "ZERO_RESULTS", 3).await;
+
+        let client = GMapsClient::builder()
+            .api_keys(&["invalid", "valid"])
+            .key_rotation(KeyRotation::RoundRobin)
+            .base_url(&server.uri())
+            .build()
+            .unwrap()
+            .validate_api_key()
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            assert!(matches!(
+                client.find_single_place_from_text("bosfor alba").await,
+                Err(GMapsClientError::ZeroResults)
+            ));
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_validation_skips_exhausted_keys() {
+        let server = MockServer::start().await;
+        // validation of both keys, then the search served by the valid one
+        mount(&server, "exhausted", "OVER_QUERY_LIMIT", 1).await;
+        mount(&server, "valid", "ZERO_RESULTS", 2).await;
+
+        let client = GMapsClient::builder()
+            .api_keys(&["exhausted", "valid"])
+            .retry_policy(RetryPolicy::none())
+            .base_url(&server.uri())
+            .build()
+            .unwrap()
+            .validate_api_key()
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            client.find_single_place_from_text("bosfor alba").await,
+            Err(GMapsClientError::ZeroResults)
+        ));
+
+        // a key out of quota is still a valid key
+        let server = MockServer::start().await;
+        mount(&server, "exhausted", "OVER_QUERY_LIMIT", 1).await;
+
+        let client = test_builder(Some(&server.uri()))
+            .api_keys(&["exhausted"])
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+        assert!(client.validate_api_key().await.is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn test_validation_fails_without_valid_key() {
+        let server = MockServer::start().await;
+        mount(&server, "first", "REQUEST_DENIED", 1).await;
+        mount(&server, "second", "REQUEST_DENIED", 1).await;
+
+        let client = GMapsClient::builder()
+            .api_keys(&["first", "second"])
+            .base_url(&server.uri())
+            .build()
+            .unwrap();
+
+        assert!(matches!(client.validate_api_key().await, Err(GMapsClientError::InvalidApiKey)));
+    }
+
+    #[tokio::test]
+    pub async fn test_requests_fail_over_to_the_next_key() {
+        let server = MockServer::start().await;
+        mount(&server, "exhausted", "OVER_QUERY_LIMIT", 1).await;
+        mount(&server, "spare", "ZERO_RESULTS", 2).await;
+
+        let client = test_builder(Some(&server.uri()))
+            .api_keys(&["exhausted", "spare"])
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        // the first request fails over, the second skips the quarantined key
+        for _ in 0..2 {
+            assert!(matches!(
+                client.find_single_place_from_text("bosfor alba").await,
+                Err(GMapsClientError::ZeroResults)
+            ));
+        }
+    }
+}
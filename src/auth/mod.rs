@@ -0,0 +1,119 @@
+use base64::engine::general_purpose::URL_SAFE;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use sha1::Sha1;
+
+use crate::query::QueryParams;
+use crate::GMapsClientError;
+
+mod keys;
+mod provider;
+mod secret;
+
+pub use keys::KeyRotation;
+pub(crate) use keys::{KeyPool, DEFAULT_KEY_COOLDOWN};
+pub use provider::{
+    ApiKeyProvider, ChainedApiKeyProvider, EnvApiKeyProvider, FileApiKeyProvider,
+    StaticApiKeyProvider,
+};
+pub(crate) use secret::REDACTED;
+pub use secret::SecretString;
+
+/// Credentials the client authenticates its requests with
+/// 
+/// Signing secrets are the url-safe base64 secrets found in the cloud console,
+/// every request is then signed with an HMAC-SHA1 signature of its path and query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Auth {
+    /// Standard api key, sent as the key parameter
+    ApiKey(SecretString),
+    /// Premium plan client id, sent as the client parameter, with its signing secret
+    ClientIdAndSecret { client_id: String, secret: SecretString },
+    /// Api key whose requests must also be signed
+    ApiKeyWithSigningSecret { api_key: SecretString, secret: SecretString },
+}
+
+impl Auth {
+
+    /// Decodes the signing secret, if any
+    /// 
+    /// returns: Result<Option<Vec<u8>>, GMapsClientError> failing with InvalidSigningSecret
+    pub(crate) fn signing_key(&self) -> Result<Option<Vec<u8>>, GMapsClientError> {
+        match self {
+            Auth::ApiKey(_) => Ok(None),
+            Auth::ClientIdAndSecret { secret, .. } | Auth::ApiKeyWithSigningSecret { secret, .. } => URL_SAFE
+                .decode(secret.expose_secret().trim())
+                .map(Some)
+                .map_err(|_| GMapsClientError::InvalidSigningSecret),
+        }
+    }
+
+    /// Appends the credential parameter identifying the caller
+    pub(crate) fn push_credentials(&self, params: &mut QueryParams) {
+        match self {
+            Auth::ApiKey(api_key) | Auth::ApiKeyWithSigningSecret { api_key, .. } => {
+                params.push("key", api_key.expose_secret())
+            }
+            Auth::ClientIdAndSecret { client_id, .. } => params.push("client", client_id),
+        }
+    }
+}
+
+/// Appends the signature parameter to the url, the HMAC-SHA1 of its path and query
+/// encoded as url-safe base64. It must be the last parameter of the url
+pub(crate) fn sign_url(mut url: Url, signing_key: &[u8]) -> Url {
+    let query = url.query().unwrap_or_default().to_string();
+    let signed = format!("{}?{}", url.path(), query);
+    let mut mac = Hmac::<Sha1>::new_from_slice(signing_key).expect("hmac accepts keys of any length");
+    mac.update(signed.as_bytes());
+    let signature = URL_SAFE.encode(mac.finalize().into_bytes());
+
+    // the signature only holds url-safe characters, appending it unencoded keeps
+    // the url identical to the one documented by google
+    url.set_query(Some(&format!("{}&signature={}", query, signature)));
+    url
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_sign_url_documented_example() {
+        // example of the digital signature guide of the maps platform
+        let auth = Auth::ClientIdAndSecret {
+            client_id: "clientID".to_string(),
+            secret: "vNIXE0xscrmjlyV-12Nj_BvUPaw=".into(),
+        };
+        let url = Url::parse("https://maps.googleapis.com/maps/api/geocode/json?address=New+York&client=clientID").unwrap();
+
+        let signed = sign_url(url, &auth.signing_key().unwrap().unwrap());
+        assert_eq!(
+            signed.as_str(),
+            "https://maps.googleapis.com/maps/api/geocode/json?address=New+York&client=clientID&signature=chaRF2hTJKOScPr-RQCEhZbSzIE="
+        );
+    }
+
+    #[test]
+    pub fn test_credential_params() {
+        let mut params = QueryParams::new();
+        Auth::ApiKeyWithSigningSecret { api_key: "test_key".into(), secret: "c2VjcmV0".into() }
+            .push_credentials(&mut params);
+        Auth::ClientIdAndSecret { client_id: "gme-test".to_string(), secret: "c2VjcmV0".into() }
+            .push_credentials(&mut params);
+
+        assert_eq!(
+            params,
+            QueryParams::from(vec![("key", "test_key".to_string()), ("client", "gme-test".to_string())])
+        );
+    }
+
+    #[test]
+    pub fn test_invalid_signing_secret() {
+        let auth = Auth::ClientIdAndSecret { client_id: "gme-test".to_string(), secret: "not base64!".into() };
+        assert!(matches!(auth.signing_key(), Err(GMapsClientError::InvalidSigningSecret)));
+        assert_eq!(Auth::ApiKey("test_key".into()).signing_key().unwrap(), None);
+    }
+}
@@ -0,0 +1,236 @@
+use std::env;
+use std::fmt::Debug;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dotenv_loader::parser::Parser;
+
+use crate::{GMapsClientError, SecretString};
+
+/// Variable the api key is read from unless another provider is configured
+const DEFAULT_API_KEY_VAR: &str = "GMAPS_API_KEY";
+
+/// Source of the api key the client is built with, selected through
+/// GMapsClientBuilder::api_key_provider
+/// 
+/// Providers fail with GMapsClientError::MissingApiKey when their source holds no
+/// key, and with GMapsClientError::ApiKeyLoadingFailure when it could not be read
+pub trait ApiKeyProvider: Debug + Send + Sync {
+
+    /// Loads the api key
+    fn api_key(&self) -> Result<SecretString, GMapsClientError>;
+}
+
+/// Trims the whitespace and the quotes surrounding a key
+fn clean_key(key: &str) -> &str {
+    let key = key.trim();
+    key.strip_prefix('"')
+        .and_then(|key| key.strip_suffix('"'))
+        .unwrap_or(key)
+}
+
+/// Reads the api key from an environment variable, optionally loading a .env file first
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvApiKeyProvider {
+    name: String,
+    dotenv: Option<PathBuf>,
+}
+
+impl EnvApiKeyProvider {
+
+    /// Reads the variable of the given name
+    pub fn new(name: &str) -> EnvApiKeyProvider {
+        EnvApiKeyProvider {
+            name: name.to_string(),
+            dotenv: None,
+        }
+    }
+
+    /// Loads the variables of the given .env file before reading the variable,
+    /// a missing file is ignored
+    pub fn dotenv(mut self, path: impl AsRef<Path>) -> Self {
+        self.dotenv = Some(path.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl Default for EnvApiKeyProvider {
+
+    /// Reads GMAPS_API_KEY after loading the .env file of the working directory
+    fn default() -> EnvApiKeyProvider {
+        EnvApiKeyProvider::new(DEFAULT_API_KEY_VAR).dotenv(".env")
+    }
+}
+
+impl ApiKeyProvider for EnvApiKeyProvider {
+    fn api_key(&self) -> Result<SecretString, GMapsClientError> {
+
+        if let Some(dotenv) = &self.dotenv {
+            let mut dotenv_parser = Parser::new();
+            let _res = dotenv_parser.parse(dotenv);
+        }
+
+        match env::var(&self.name) {
+            Ok(api_key) if !clean_key(&api_key).is_empty() => Ok(clean_key(&api_key).into()),
+            Ok(_) | Err(env::VarError::NotPresent) => {
+                Err(GMapsClientError::MissingApiKey(format!("the {} variable", self.name)))
+            }
+            Err(env::VarError::NotUnicode(_)) => Err(GMapsClientError::ApiKeyLoadingFailure {
+                source_name: format!("the {} variable", self.name),
+                source: io::Error::new(io::ErrorKind::InvalidData, "the value is not valid unicode"),
+            }),
+        }
+    }
+}
+
+/// Provides an api key given explicitly, such as one fetched from a secret manager
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticApiKeyProvider(SecretString);
+
+impl StaticApiKeyProvider {
+    pub fn new(api_key: &str) -> StaticApiKeyProvider {
+        StaticApiKeyProvider(api_key.into())
+    }
+}
+
+impl ApiKeyProvider for StaticApiKeyProvider {
+    fn api_key(&self) -> Result<SecretString, GMapsClientError> {
+        Ok(self.0.clone())
+    }
+}
+
+/// Reads the api key from a file holding only the key, such as a mounted kubernetes secret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileApiKeyProvider {
+    path: PathBuf,
+}
+
+impl FileApiKeyProvider {
+    pub fn new(path: impl AsRef<Path>) -> FileApiKeyProvider {
+        FileApiKeyProvider {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl ApiKeyProvider for FileApiKeyProvider {
+    fn api_key(&self) -> Result<SecretString, GMapsClientError> {
+
+        let source_name = format!("the file {}", self.path.display());
+
+        match std::fs::read_to_string(&self.path) {
+            Ok(api_key) if !clean_key(&api_key).is_empty() => Ok(clean_key(&api_key).into()),
+            Ok(_) => Err(GMapsClientError::MissingApiKey(source_name)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                Err(GMapsClientError::MissingApiKey(source_name))
+            }
+            Err(error) => Err(GMapsClientError::ApiKeyLoadingFailure { source_name, source: error }),
+        }
+    }
+}
+
+/// Asks each provider in turn, returning the first key found
+/// 
+/// A provider whose source holds no key is skipped, while a source that could not
+/// be read fails the chain rather than silently falling back to the next provider
+#[derive(Debug, Clone, Default)]
+pub struct ChainedApiKeyProvider {
+    providers: Vec<Arc<dyn ApiKeyProvider>>,
+}
+
+impl ChainedApiKeyProvider {
+
+    pub fn new() -> ChainedApiKeyProvider {
+        ChainedApiKeyProvider::default()
+    }
+
+    /// Asks the given provider after the ones added before it
+    pub fn with(mut self, provider: Arc<dyn ApiKeyProvider>) -> Self {
+        self.providers.push(provider);
+        self
+    }
+}
+
+impl ApiKeyProvider for ChainedApiKeyProvider {
+    fn api_key(&self) -> Result<SecretString, GMapsClientError> {
+
+        let mut searched = Vec::new();
+        for provider in &self.providers {
+            match provider.api_key() {
+                Err(GMapsClientError::MissingApiKey(source_name)) => searched.push(source_name),
+                result => return result,
+            }
+        }
+
+        Err(GMapsClientError::MissingApiKey(searched.join(", ")))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("gmaps_client_{}_{}", name, std::process::id()))
+    }
+
+    #[test]
+    pub fn test_env_provider() {
+        env::set_var("GMAPS_CLIENT_TEST_KEY", "\"env_key\"");
+
+        assert_eq!(EnvApiKeyProvider::new("GMAPS_CLIENT_TEST_KEY").api_key().unwrap().expose_secret(), "env_key");
+        assert!(matches!(
+            EnvApiKeyProvider::new("GMAPS_CLIENT_TEST_UNSET_KEY").api_key(),
+            Err(GMapsClientError::MissingApiKey(_))
+        ));
+    }
+
+    #[test]
+    pub fn test_file_provider() {
+        let path = temp_path("file_key");
+        std::fs::write(&path, "file_key\n").unwrap();
+        let result = FileApiKeyProvider::new(&path).api_key();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap().expose_secret(), "file_key");
+    }
+
+    #[test]
+    pub fn test_file_provider_distinguishes_missing_from_unreadable() {
+        assert!(matches!(
+            FileApiKeyProvider::new(temp_path("missing_key")).api_key(),
+            Err(GMapsClientError::MissingApiKey(_))
+        ));
+
+        // reading a directory fails with an error other than not found
+        assert!(matches!(
+            FileApiKeyProvider::new(env::temp_dir()).api_key(),
+            Err(GMapsClientError::ApiKeyLoadingFailure { .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_chained_provider() {
+        let chain = ChainedApiKeyProvider::new()
+            .with(Arc::new(FileApiKeyProvider::new(temp_path("missing_key"))))
+            .with(Arc::new(EnvApiKeyProvider::new("GMAPS_CLIENT_TEST_UNSET_KEY")))
+            .with(Arc::new(StaticApiKeyProvider::new("static_key")));
+        assert_eq!(chain.api_key().unwrap().expose_secret(), "static_key");
+
+        let chain = ChainedApiKeyProvider::new()
+            .with(Arc::new(FileApiKeyProvider::new(env::temp_dir())))
+            .with(Arc::new(StaticApiKeyProvider::new("static_key")));
+        assert!(matches!(chain.api_key(), Err(GMapsClientError::ApiKeyLoadingFailure { .. })));
+
+        let chain = ChainedApiKeyProvider::new()
+            .with(Arc::new(EnvApiKeyProvider::new("GMAPS_CLIENT_TEST_UNSET_KEY")));
+        match chain.api_key() {
+            Err(GMapsClientError::MissingApiKey(searched)) => {
+                assert_eq!(searched, "the GMAPS_CLIENT_TEST_UNSET_KEY variable")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use std::fmt;
+
+/// Text printed in place of a secret
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// A credential such as an api key or a signing secret, whose Debug and Display
+/// print a redacted form so that logging a client or its builder leaks nothing
+#[derive(Clone, PartialEq, Eq)]
+pub struct SecretString(String);
+
+impl SecretString {
+
+    pub fn new(secret: &str) -> SecretString {
+        SecretString(secret.to_string())
+    }
+
+    /// Returns the secret itself, to be sent to the api
+    pub fn expose_secret(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for SecretString {
+    fn from(secret: String) -> SecretString {
+        SecretString(secret)
+    }
+}
+
+impl From<&str> for SecretString {
+    fn from(secret: &str) -> SecretString {
+        SecretString::new(secret)
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SecretString").field(&REDACTED).finish()
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
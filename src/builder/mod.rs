@@ -0,0 +1,483 @@
+use reqwest::Url;
+
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::directions::Units;
+use crate::cache::{Cache, DEFAULT_TTL};
+use crate::auth::{KeyPool, DEFAULT_KEY_COOLDOWN};
+use crate::rate_limit::RateLimiter;
+use crate::{
+    Api, ApiKeyProvider, Auth, GMapsClient, GMapsClientError, Invalidated, KeyRotation, RateLimit,
+    RetryPolicy,
+};
+
+/// User agent sent with every request, followed by the configured suffix
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Configures and constructs a GMapsClient
+/// 
+/// By default the api key is loaded from the GMAPS_API_KEY variable, see EnvApiKeyProvider,
+/// every api is reached at the google hosts and a new reqwest::Client is created
+#[derive(Debug, Default)]
+pub struct GMapsClientBuilder {
+    auths: Vec<Auth>,
+    key_rotation: KeyRotation,
+    key_cooldown: Option<Duration>,
+    api_key_provider: Option<Arc<dyn ApiKeyProvider>>,
+    base_url: Option<String>,
+    api_base_urls: HashMap<Api, String>,
+    http: Option<reqwest::Client>,
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent_suffix: Option<String>,
+    language: Option<String>,
+    region: Option<String>,
+    units: Option<Units>,
+    retry_policy: Option<RetryPolicy>,
+    rate_limits: HashMap<Api, RateLimit>,
+    cache: Option<Arc<dyn Cache>>,
+    cache_ttl: Option<Duration>,
+}
+
+impl GMapsClientBuilder {
+
+    pub fn new() -> GMapsClientBuilder {
+        GMapsClientBuilder::default()
+    }
+
+    /// Uses the given api key instead of loading it from the environment
+    pub fn api_key(mut self, api_key: &str) -> Self {
+        self.auths = vec![Auth::ApiKey(api_key.into())];
+        self
+    }
+
+    /// Spreads the requests over several api keys, such as keys billed to different
+    /// projects, according to the key rotation. Each key is validated independently
+    pub fn api_keys(mut self, api_keys: &[&str]) -> Self {
+        self.auths = api_keys.iter().map(|api_key| Auth::ApiKey((*api_key).into())).collect();
+        self
+    }
+
+    /// Loads the api key from the given provider when the client is built, unless
+    /// credentials were given through api_key or auth
+    pub fn api_key_provider(mut self, api_key_provider: Arc<dyn ApiKeyProvider>) -> Self {
+        self.api_key_provider = Some(api_key_provider);
+        self
+    }
+
+    /// Authenticates with the given credentials, such as a premium plan client id
+    /// and signing secret, instead of loading an api key from the environment
+    pub fn auth(mut self, auth: Auth) -> Self {
+        self.auths = vec![auth];
+        self
+    }
+
+    /// How the requests are spread over the keys given through api_keys, failing over
+    /// to the next key by default
+    pub fn key_rotation(mut self, key_rotation: KeyRotation) -> Self {
+        self.key_rotation = key_rotation;
+        self
+    }
+
+    /// Time a key is left unused after it was denied or ran out of quota
+    pub fn key_cooldown(mut self, key_cooldown: Duration) -> Self {
+        self.key_cooldown = Some(key_cooldown);
+        self
+    }
+
+    /// Sends the requests of every api to the given base url, such as a local mock server
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = Some(base_url.to_string());
+        self
+    }
+
+    /// Sends the requests of a single api to the given base url, taking precedence over base_url
+    pub fn api_base_url(mut self, api: Api, base_url: &str) -> Self {
+        self.api_base_urls.insert(api, base_url.to_string());
+        self
+    }
+
+    /// Sends the requests through the given client, sharing its connection pool, proxy
+    /// and tls configuration. The connect timeout is not applied to such a client
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// Maximum time spent establishing a connection
+    pub fn connect_timeout(mut self, connect_timeout: Duration) -> Self {
+        self.connect_timeout = Some(connect_timeout);
+        self
+    }
+
+    /// Maximum time spent on a request, from connecting until the body is read.
+    /// Also applied to a client given through http_client
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Appends the given text to the user agent of the requests, such as "ev-charger/1.2"
+    pub fn user_agent_suffix(mut self, suffix: &str) -> Self {
+        self.user_agent_suffix = Some(suffix.to_string());
+        self
+    }
+
+    /// Language of the results, as an IETF language tag such as "ro"
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Region biasing the results, as a ccTLD code such as "ro"
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Unit system of the distances, unless a request overrides it
+    pub fn units(mut self, units: Units) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Retries failed requests according to the given policy, RetryPolicy::default() otherwise
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Limits the rate of the requests sent to the given api. The limit is shared by
+    /// every clone of the client
+    pub fn rate_limit(mut self, api: Api, rate_limit: RateLimit) -> Self {
+        self.rate_limits.insert(api, rate_limit);
+        self
+    }
+
+    /// Caches the successful responses of the places and geocoding apis
+    pub fn cache(mut self, cache: Arc<dyn Cache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Time cached responses are kept for, capped at cache::MAX_CONTENT_TTL.
+    /// Responses limited to place ids are kept indefinitely
+    pub fn cache_ttl(mut self, cache_ttl: Duration) -> Self {
+        self.cache_ttl = Some(cache_ttl);
+        self
+    }
+
+    /// Constructs the client, loading the api key from the provider if no credentials were given
+    /// 
+    /// returns: Result<GMapsClient<Invalidated>, GMapsClientError>
+    pub fn build(self) -> Result<GMapsClient<Invalidated>, GMapsClientError> {
+
+        let auths = match (self.auths.is_empty(), self.api_key_provider) {
+            (false, _) => self.auths,
+            (true, Some(provider)) => vec![Auth::ApiKey(provider.api_key()?)],
+            (true, None) => vec![Auth::ApiKey(GMapsClient::load_api_key()?)],
+        };
+        let keys = KeyPool::new(
+            auths,
+            self.key_rotation,
+            self.key_cooldown.unwrap_or(DEFAULT_KEY_COOLDOWN),
+        )?;
+
+        let mut base_urls = HashMap::new();
+        for api in Api::ALL {
+            let base_url = self
+                .api_base_urls
+                .get(&api)
+                .or(self.base_url.as_ref())
+                .map(String::as_str)
+                .unwrap_or_else(|| api.default_base_url());
+
+            if Url::parse(base_url).is_err() {
+                return Err(GMapsClientError::InvalidBaseUrl(base_url.to_string()));
+            }
+
+            base_urls.insert(api, base_url.trim_end_matches('/').to_string());
+        }
+
+        let http = match self.http {
+            Some(http) => http,
+            None => {
+                let mut http = reqwest::Client::builder();
+                if let Some(connect_timeout) = self.connect_timeout {
+                    http = http.connect_timeout(connect_timeout);
+                }
+                http.build().map_err(GMapsClientError::request_failure)?
+            }
+        };
+
+        let user_agent = match self.user_agent_suffix {
+            Some(suffix) => format!("{} {}", USER_AGENT, suffix),
+            None => USER_AGENT.to_string(),
+        };
+
+        let rate_limiters = self
+            .rate_limits
+            .into_iter()
+            .map(|(api, rate_limit)| (api, RateLimiter::new(rate_limit)))
+            .collect();
+
+        Ok(GMapsClient {
+            keys: Arc::new(keys),
+            base_urls,
+            http,
+            user_agent,
+            language: self.language,
+            region: self.region,
+            units: self.units,
+            timeout: self.timeout,
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            rate_limiters: Arc::new(rate_limiters),
+            cache: self.cache,
+            cache_ttl: self.cache_ttl.unwrap_or(DEFAULT_TTL),
+            state: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::{test_builder, EnvApiKeyProvider, GMapsClientError, StaticApiKeyProvider};
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    pub fn test_base_url_precedence() {
+        let client = GMapsClient::builder()
+            .api_key("test_key")
+            .base_url("http://localhost:8080/")
+            .api_base_url(Api::Geolocation, "http://localhost:9090")
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(Api::Places), "http://localhost:8080");
+        assert_eq!(client.base_url(Api::Directions), "http://localhost:8080");
+        assert_eq!(client.base_url(Api::Geolocation), "http://localhost:9090");
+    }
+
+    #[test]
+    pub fn test_default_base_urls() {
+        let client = test_builder(None).build().unwrap();
+
+        assert_eq!(client.base_url(Api::Places), "https://maps.googleapis.com");
+        assert_eq!(client.base_url(Api::Geolocation), "https://www.googleapis.com");
+    }
+
+    #[test]
+    pub fn test_invalid_base_url() {
+        let client = GMapsClient::builder()
+            .api_key("test_key")
+            .base_url("not a url")
+            .build();
+
+        assert!(matches!(client, Err(GMapsClientError::InvalidBaseUrl(_))));
+    }
+
+    #[test]
+    pub fn test_api_key_provider() {
+        let client = GMapsClient::builder()
+            .api_key_provider(Arc::new(StaticApiKeyProvider::new("provided_key")))
+            .build()
+            .unwrap();
+        assert_eq!(client.keys.auths(), vec![&Auth::ApiKey("provided_key".into())]);
+
+        let client = GMapsClient::builder()
+            .api_key_provider(Arc::new(StaticApiKeyProvider::new("provided_key")))
+            .api_key("test_key")
+            .build()
+            .unwrap();
+        assert_eq!(client.keys.auths(), vec![&Auth::ApiKey("test_key".into())]);
+
+        let client = GMapsClient::builder()
+            .api_key_provider(Arc::new(EnvApiKeyProvider::new("GMAPS_CLIENT_TEST_UNSET_KEY")))
+            .build();
+        assert!(matches!(client, Err(GMapsClientError::MissingApiKey(_))));
+    }
+
+    #[tokio::test]
+    pub async fn test_credentials_are_redacted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let builder = GMapsClient::builder()
+            .api_keys(&["first_secret_key", "second_secret_key"])
+            .base_url(&server.uri())
+            .retry_policy(RetryPolicy::none());
+        assert!(!format!("{:?}", builder).contains("secret_key"));
+
+        let client = builder.build().unwrap();
+        assert!(!format!("{:?}", client).contains("secret_key"));
+
+        let signed = GMapsClient::builder()
+            .auth(Auth::ApiKeyWithSigningSecret {
+                api_key: "signed_secret_key".into(),
+                secret: "vNIXE0xscrmjlyV-12Nj_BvUPaw=".into(),
+            })
+            .build()
+            .unwrap();
+        let debug = format!("{:?}", signed);
+        assert!(!debug.contains("secret_key") && !debug.contains("vNIXE0xscrmjlyV"));
+
+        let error = client.assume_validated().geocode("Alba Iulia").send().await.unwrap_err();
+        let source = std::error::Error::source(&error).unwrap().to_string();
+        assert!(source.contains("key=%5BREDACTED%5D"), "{}", source);
+        assert!(!format!("{:?}", error).contains("secret_key"));
+    }
+
+    #[test]
+    pub fn test_invalid_signing_secret() {
+        let client = GMapsClient::builder()
+            .auth(Auth::ClientIdAndSecret {
+                client_id: "clientID".to_string(),
+                secret: "not base64!".into(),
+            })
+            .build();
+
+        assert!(matches!(client, Err(GMapsClientError::InvalidSigningSecret)));
+    }
+
+    #[tokio::test]
+    pub async fn test_requests_are_signed() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/geocode/json"))
+            .and(query_param("address", "New York"))
+            .and(query_param("client", "clientID"))
+            .and(query_param("signature", "chaRF2hTJKOScPr-RQCEhZbSzIE="))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_string(r#"{ "results": [], "status": "OK" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        // the credentials of the digital signature guide of the maps platform
+        let client = GMapsClient::builder()
+            .auth(Auth::ClientIdAndSecret {
+                client_id: "clientID".to_string(),
+                secret: "vNIXE0xscrmjlyV-12Nj_BvUPaw=".into(),
+            })
+            .base_url(&server.uri())
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        assert!(client.geocode("New York").send().await.is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn test_validation_uses_base_url() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/findplacefromtext/json"))
+            .and(query_param("key", "test_key"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "candidates": [], "status": "ZERO_RESULTS" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_builder(Some(&server.uri()))
+            .build()
+            .unwrap();
+
+        assert!(client.validate_api_key().await.is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn test_client_defaults_are_sent() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/directions/json"))
+            .and(query_param("language", "ro"))
+            .and(query_param("region", "ro"))
+            .and(query_param("units", "imperial"))
+            .and(header("user-agent", USER_AGENT.to_string() + " ev-charger/1.2"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "routes": [], "status": "ZERO_RESULTS" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_builder(Some(&server.uri()))
+            .http_client(reqwest::Client::new())
+            .user_agent_suffix("ev-charger/1.2")
+            .language("ro")
+            .region("ro")
+            .units(Units::Metric)
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        let response = client
+            .directions("Alba Iulia", "Cluj-Napoca")
+            .units(Units::Imperial)
+            .send()
+            .await;
+        assert!(matches!(response, Err(GMapsClientError::ZeroResults)));
+    }
+
+    #[tokio::test]
+    pub async fn test_request_timeout() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "candidates": [], "status": "OK" }"#)
+                    .set_delay(Duration::from_millis(500)),
+            )
+            .mount(&server)
+            .await;
+
+        let client = test_builder(Some(&server.uri()))
+            .timeout(Duration::from_millis(50))
+            .retry_policy(RetryPolicy::none())
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            client.validate_api_key().await,
+            Err(GMapsClientError::RequestFailure(_))
+        ));
+    }
+
+    #[tokio::test]
+    pub async fn test_validation_rejects_denied_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/findplacefromtext/json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "candidates": [], "status": "REQUEST_DENIED" }"#),
+            )
+            .mount(&server)
+            .await;
+
+        let client = GMapsClient::builder()
+            .api_key("invalid_key")
+            .base_url(&server.uri())
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            client.validate_api_key().await,
+            Err(GMapsClientError::InvalidApiKey)
+        ));
+    }
+}
@@ -0,0 +1,280 @@
+use lru::LruCache;
+use reqwest::Url;
+use tokio::time::Instant;
+
+use std::fmt::Debug;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::{Api, GMapsClient};
+
+#[cfg(feature = "sqlite-cache")]
+mod sqlite;
+
+#[cfg(feature = "sqlite-cache")]
+pub use sqlite::{CacheStats, SqliteCache};
+
+/// Longest time content other than place ids may be kept, per the google maps platform terms
+pub const MAX_CONTENT_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Time content is kept for when the client does not configure it
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Apis whose responses are cached
+const CACHED_APIS: [Api; 2] = [Api::Places, Api::Geocoding];
+
+/// Query parameters identifying the caller rather than the request, left out of the keys
+const CREDENTIAL_PARAMS: [&str; 3] = ["key", "client", "signature"];
+
+/// Storage for the bodies of successful responses, keyed on the normalized request
+/// 
+/// Implementations are shared by every clone of the client and must therefore be
+/// safe to use from several tasks at once. They are called on the blocking threads
+/// of the runtime, so they may block on disk or network access
+pub trait Cache: Debug + Send + Sync {
+
+    /// Returns the body stored under the key, unless it expired
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Stores a body under the key, for the given time or indefinitely when ttl is None
+    fn insert(&self, key: &str, body: &str, ttl: Option<Duration>);
+}
+
+#[derive(Debug)]
+struct Entry {
+    body: String,
+    expires_at: Option<Instant>,
+}
+
+/// In memory cache evicting the least recently used entries past its capacity
+#[derive(Debug)]
+pub struct MemoryCache {
+    entries: Mutex<LruCache<String, Entry>>,
+}
+
+impl MemoryCache {
+
+    /// Creates a cache holding at most capacity responses
+    pub fn new(capacity: usize) -> MemoryCache {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+
+        MemoryCache {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("cache lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Cache for MemoryCache {
+
+    fn get(&self, key: &str) -> Option<String> {
+        let mut entries = self.entries.lock().expect("cache lock poisoned");
+
+        let expired = match entries.get(key) {
+            Some(entry) => entry.expires_at.is_some_and(|at| at <= Instant::now()),
+            None => return None,
+        };
+
+        if expired {
+            entries.pop(key);
+            return None;
+        }
+
+        entries.get(key).map(|entry| entry.body.clone())
+    }
+
+    fn insert(&self, key: &str, body: &str, ttl: Option<Duration>) {
+        let entry = Entry {
+            body: body.to_string(),
+            expires_at: ttl.map(|ttl| Instant::now() + ttl),
+        };
+
+        self.entries
+            .lock()
+            .expect("cache lock poisoned")
+            .put(key.to_string(), entry);
+    }
+}
+
+/// Builds the cache key of a request: its path followed by its sorted query parameters,
+/// credentials excluded. Requests of an autocomplete session are not cached, their token
+/// is never reused and the place details ending a session must reach the api to close it
+pub(crate) fn cache_key(url: &str) -> Option<String> {
+    let url = Url::parse(url).ok()?;
+
+    if url.query_pairs().any(|(name, _)| name == "sessiontoken") {
+        return None;
+    }
+
+    let mut params: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !CREDENTIAL_PARAMS.contains(&name.as_ref()))
+        .map(|(name, value)| (name.into_owned(), value.trim().to_string()))
+        .collect();
+    params.sort();
+
+    let query = Url::parse_with_params("cache:", &params).ok()?;
+    Some(format!("{}?{}", url.path(), query.query().unwrap_or_default()))
+}
+
+/// Time a response may be cached for. Responses limited to place ids can be kept
+/// indefinitely, any other content at most MAX_CONTENT_TTL
+fn cache_ttl(url: &str, ttl: Duration) -> Option<Duration> {
+    let only_place_ids = Url::parse(url)
+        .map(|url| url.query_pairs().any(|(name, value)| name == "fields" && value == "place_id"))
+        .unwrap_or(false);
+
+    if only_place_ids {
+        None
+    } else {
+        Some(ttl.min(MAX_CONTENT_TTL))
+    }
+}
+
+impl<T> GMapsClient<T> {
+
+    /// Key the response of the request is cached under, None if it should not be cached
+    pub(crate) fn cache_key(&self, api: Api, url: &str) -> Option<String> {
+        if self.cache.is_none() || !CACHED_APIS.contains(&api) {
+            return None;
+        }
+        cache_key(url)
+    }
+
+    /// Looks the key up on a blocking thread, caches backed by disk must not stall the executor
+    pub(crate) async fn cache_get(&self, key: &str) -> Option<String> {
+        let cache = self.cache.clone()?;
+        let key = key.to_string();
+
+        tokio::task::spawn_blocking(move || cache.get(&key)).await.ok().flatten()
+    }
+
+    /// Stores the body on a blocking thread, see cache_get
+    pub(crate) async fn cache_insert(&self, key: &str, url: &str, body: &str) {
+        if let Some(cache) = self.cache.clone() {
+            let key = key.to_string();
+            let body = body.to_string();
+            let ttl = cache_ttl(url, self.cache_ttl);
+
+            let _ = tokio::task::spawn_blocking(move || cache.insert(&key, &body, ttl)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_builder;
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    pub fn test_cache_key_is_normalized() {
+        let first = cache_key("https://maps.googleapis.com/maps/api/geocode/json?key=first&address=Alba+Iulia&region=ro");
+        let second = cache_key("http://localhost:8080/maps/api/geocode/json?region=ro&address=%20Alba%20Iulia&key=second");
+
+        assert_eq!(first, second);
+        assert_eq!(first.unwrap(), "/maps/api/geocode/json?address=Alba+Iulia&region=ro");
+    }
+
+    #[test]
+    pub fn test_session_requests_are_not_cached() {
+        let details = "https://maps.googleapis.com/maps/api/place/details/json?place_id=x&sessiontoken=abc&key=k";
+        let autocomplete = "https://maps.googleapis.com/maps/api/place/autocomplete/json?input=x&sessiontoken=abc";
+
+        assert_eq!(cache_key(details), None);
+        assert_eq!(cache_key(autocomplete), None);
+        assert!(cache_key("https://maps.googleapis.com/maps/api/place/details/json?place_id=x").is_some());
+    }
+
+    #[test]
+    pub fn test_cache_ttl_follows_terms() {
+        let place_ids = "https://maps.googleapis.com/maps/api/place/findplacefromtext/json?input=x&fields=place_id";
+        let content = "https://maps.googleapis.com/maps/api/place/findplacefromtext/json?input=x&fields=place_id,name";
+
+        assert_eq!(cache_ttl(place_ids, DEFAULT_TTL), None);
+        assert_eq!(cache_ttl(content, DEFAULT_TTL), Some(DEFAULT_TTL));
+        assert_eq!(cache_ttl(content, MAX_CONTENT_TTL * 2), Some(MAX_CONTENT_TTL));
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_memory_cache_expiry_and_eviction() {
+        let cache = MemoryCache::new(2);
+
+        cache.insert("a", "1", Some(Duration::from_secs(10)));
+        cache.insert("b", "2", None);
+        assert_eq!(cache.get("a").as_deref(), Some("1"));
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("b").as_deref(), Some("2"));
+
+        cache.insert("c", "3", None);
+        cache.insert("d", "4", None);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[tokio::test]
+    pub async fn test_repeated_lookups_are_served_from_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/geocode/json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "results": [], "status": "OK" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let cache = Arc::new(MemoryCache::new(100));
+        let client = test_builder(Some(&server.uri()))
+            .cache(cache.clone())
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        for _ in 0..3 {
+            assert!(client.geocode("Alba Iulia").send().await.is_ok());
+        }
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[tokio::test]
+    pub async fn test_pages_with_a_next_page_token_are_not_cached() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/textsearch/json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "results": [], "status": "OK", "next_page_token": "second" }"#),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let cache = Arc::new(MemoryCache::new(100));
+        let client = test_builder(Some(&server.uri()))
+            .cache(cache.clone())
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        for _ in 0..2 {
+            let response = client.find_places_from_text("chargers").await.unwrap();
+            assert_eq!(response.next_page_token.as_deref(), Some("second"));
+        }
+        assert!(cache.is_empty());
+    }
+}
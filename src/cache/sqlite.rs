@@ -0,0 +1,317 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::cache::Cache;
+use crate::GMapsClientError;
+
+/// Total size of the stored bodies when the cache does not configure it
+const DEFAULT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS responses (
+        key TEXT PRIMARY KEY,
+        body TEXT NOT NULL,
+        size INTEGER NOT NULL,
+        expires_at INTEGER,
+        accessed INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS responses_accessed ON responses (accessed);
+";
+
+/// Usage statistics of a SqliteCache, hits and misses being counted since it was opened
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: u64,
+    pub size_bytes: u64,
+}
+
+/// Cache persisting the responses in a sqlite database, so that they survive restarts
+/// 
+/// Once the stored bodies exceed the configured size the least recently used ones are
+/// evicted. Expired entries are skipped when read and removed by the next insert or by vacuum.
+/// Reads do not write to the database, their accesses are recorded along with the next insert
+#[derive(Debug)]
+pub struct SqliteCache {
+    connection: Mutex<Connection>,
+    max_bytes: u64,
+    /// Logical clock ordering the accesses, used for the eviction
+    clock: AtomicU64,
+    /// Accesses of the hits not written yet, by key
+    accesses: Mutex<HashMap<String, i64>>,
+    /// Total size of the stored bodies, tracked to evict only when it exceeds max_bytes
+    size_bytes: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+impl SqliteCache {
+
+    /// Opens the cache stored at the given path, creating the database if needed.
+    /// The database is put in WAL mode, syncing to disk at checkpoints only
+    /// 
+    /// returns: Result<SqliteCache, GMapsClientError>
+    pub fn open(path: impl AsRef<Path>) -> Result<SqliteCache, GMapsClientError> {
+        let connection = Connection::open(path)?;
+        connection.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))?;
+        connection.pragma_update(None, "synchronous", "NORMAL")?;
+
+        SqliteCache::with_connection(connection)
+    }
+
+    /// Opens a cache living in memory only, mostly useful for tests
+    /// 
+    /// returns: Result<SqliteCache, GMapsClientError>
+    pub fn open_in_memory() -> Result<SqliteCache, GMapsClientError> {
+        SqliteCache::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<SqliteCache, GMapsClientError> {
+        connection.execute_batch(SCHEMA)?;
+
+        let (clock, size_bytes): (i64, i64) = connection.query_row(
+            "SELECT COALESCE(MAX(accessed), 0), COALESCE(SUM(size), 0) FROM responses",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(SqliteCache {
+            connection: Mutex::new(connection),
+            max_bytes: DEFAULT_MAX_BYTES,
+            clock: AtomicU64::new(clock as u64),
+            accesses: Mutex::new(HashMap::new()),
+            size_bytes: AtomicU64::new(size_bytes as u64),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        })
+    }
+
+    /// Maximum total size of the stored bodies, in bytes
+    pub fn max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Removes the expired entries and compacts the database file
+    /// 
+    /// returns: Result<usize, GMapsClientError>, the number of removed entries
+    pub fn vacuum(&self) -> Result<usize, GMapsClientError> {
+        let connection = self.connection.lock().expect("cache lock poisoned");
+
+        let removed = connection.execute(
+            "DELETE FROM responses WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+            params![now_millis()],
+        )?;
+        connection.execute_batch("VACUUM")?;
+        self.size_bytes.store(total_size(&connection)?, Ordering::Relaxed);
+
+        Ok(removed)
+    }
+
+    /// returns: Result<CacheStats, GMapsClientError>
+    pub fn stats(&self) -> Result<CacheStats, GMapsClientError> {
+        let connection = self.connection.lock().expect("cache lock poisoned");
+
+        let (entries, size_bytes): (i64, i64) = connection.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(size), 0) FROM responses",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: entries as u64,
+            size_bytes: size_bytes as u64,
+        })
+    }
+
+    fn tick(&self) -> i64 {
+        (self.clock.fetch_add(1, Ordering::Relaxed) + 1) as i64
+    }
+
+    fn lookup(&self, key: &str) -> Result<Option<String>, rusqlite::Error> {
+        let connection = self.connection.lock().expect("cache lock poisoned");
+
+        let entry: Option<(String, Option<i64>)> = connection
+            .query_row(
+                "SELECT body, expires_at FROM responses WHERE key = ?1",
+                params![key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        match entry {
+            Some((_, Some(expires_at))) if expires_at <= now_millis() => Ok(None),
+            Some((body, _)) => {
+                let accessed = self.tick();
+                self.accesses
+                    .lock()
+                    .expect("cache lock poisoned")
+                    .insert(key.to_string(), accessed);
+                Ok(Some(body))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn store(&self, key: &str, body: &str, ttl: Option<Duration>) -> Result<(), rusqlite::Error> {
+        let mut connection = self.connection.lock().expect("cache lock poisoned");
+        let expires_at = ttl.map(|ttl| now_millis().saturating_add(ttl.as_millis() as i64));
+        let accesses = std::mem::take(&mut *self.accesses.lock().expect("cache lock poisoned"));
+
+        let transaction = connection.transaction()?;
+
+        for (accessed_key, accessed) in accesses {
+            transaction.execute(
+                "UPDATE responses SET accessed = ?1 WHERE key = ?2",
+                params![accessed, accessed_key],
+            )?;
+        }
+
+        let expired = transaction.execute(
+            "DELETE FROM responses WHERE expires_at IS NOT NULL AND expires_at <= ?1",
+            params![now_millis()],
+        )?;
+        let stored = match expired {
+            0 => self.size_bytes.load(Ordering::Relaxed),
+            _ => total_size(&transaction)?,
+        };
+
+        let replaced: i64 = transaction
+            .query_row("SELECT size FROM responses WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()?
+            .unwrap_or(0);
+
+        transaction.execute(
+            "INSERT OR REPLACE INTO responses (key, body, size, expires_at, accessed)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![key, body, body.len() as i64, expires_at, self.tick()],
+        )?;
+
+        let mut size_bytes = (stored + body.len() as u64)
+            .saturating_sub(replaced as u64);
+
+        if size_bytes > self.max_bytes {
+            // keeps the most recently used entries whose sizes add up to at most max_bytes
+            transaction.execute(
+                "DELETE FROM responses WHERE key IN (
+                    SELECT key FROM (
+                        SELECT key, SUM(size) OVER (ORDER BY accessed DESC) AS total
+                        FROM responses
+                    ) WHERE total > ?1
+                )",
+                params![self.max_bytes as i64],
+            )?;
+            size_bytes = total_size(&transaction)?;
+        }
+
+        transaction.commit()?;
+        self.size_bytes.store(size_bytes, Ordering::Relaxed);
+
+        Ok(())
+    }
+}
+
+fn total_size(connection: &Connection) -> Result<u64, rusqlite::Error> {
+    connection
+        .query_row("SELECT COALESCE(SUM(size), 0) FROM responses", [], |row| row.get::<_, i64>(0))
+        .map(|size| size as u64)
+}
+
+impl Cache for SqliteCache {
+
+    fn get(&self, key: &str) -> Option<String> {
+        let body = self.lookup(key).ok().flatten();
+
+        let counter = if body.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        body
+    }
+
+    fn insert(&self, key: &str, body: &str, ttl: Option<Duration>) {
+        // a failing cache only costs an extra request, it must not fail the lookup
+        let _ = self.store(key, body, ttl);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_sqlite_cache_hits_and_expiry() {
+        let cache = SqliteCache::open_in_memory().unwrap();
+
+        cache.insert("place", "body", None);
+        cache.insert("expired", "body", Some(Duration::ZERO));
+
+        assert_eq!(cache.get("place").as_deref(), Some("body"));
+        assert_eq!(cache.get("expired"), None);
+        assert_eq!(cache.get("missing"), None);
+
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 2, 2));
+
+        // the expired entry is left to the next insert
+        cache.insert("other", "body", None);
+        let stats = cache.stats().unwrap();
+        assert_eq!((stats.entries, stats.size_bytes), (2, 8));
+    }
+
+    #[test]
+    pub fn test_sqlite_cache_size_eviction() {
+        let cache = SqliteCache::open_in_memory().unwrap().max_bytes(10);
+
+        cache.insert("a", "aaaa", None);
+        cache.insert("b", "bbbb", None);
+        assert!(cache.get("a").is_some());
+        cache.insert("c", "cccc", None);
+
+        assert!(cache.get("a").is_some());
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_some());
+        assert_eq!(cache.stats().unwrap().size_bytes, 8);
+    }
+
+    #[test]
+    pub fn test_sqlite_cache_persists_and_vacuums() {
+        let path = std::env::temp_dir().join(format!("gmaps_client_cache_{}.sqlite", std::process::id()));
+
+        {
+            let cache = SqliteCache::open(&path).unwrap();
+            cache.insert("kept", "body", None);
+            cache.insert("expired", "body", Some(Duration::ZERO));
+        }
+
+        let cache = SqliteCache::open(&path).unwrap();
+        let journal_mode: String = cache
+            .connection
+            .lock()
+            .unwrap()
+            .query_row("PRAGMA journal_mode", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(journal_mode, "wal");
+        assert_eq!(cache.vacuum().unwrap(), 1);
+        assert_eq!(cache.get("kept").as_deref(), Some("body"));
+        assert_eq!(cache.stats().unwrap().entries, 1);
+
+        drop(cache);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,524 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::polyline;
+use crate::types::{Bounds, Distance, LatLng, TravelDuration};
+use crate::{Api, ApiResponse, GMapsClient, GMapsClientError, Validated};
+
+/// Status codes returned by the directions api
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DirectionsStatus {
+    Ok,
+    NotFound,
+    ZeroResults,
+    MaxWaypointsExceeded,
+    MaxRouteLengthExceeded,
+    InvalidRequest,
+    OverDailyLimit,
+    OverQueryLimit,
+    RequestDenied,
+    UnknownError,
+}
+
+impl DirectionsStatus {
+
+    /// Maps every status other than OK into the matching GMapsClientError
+    pub(crate) fn check(self, error_message: &Option<String>) -> Result<(), GMapsClientError> {
+        match self {
+            DirectionsStatus::Ok => Ok(()),
+            DirectionsStatus::NotFound => Err(GMapsClientError::NotFound),
+            DirectionsStatus::ZeroResults => Err(GMapsClientError::ZeroResults),
+            DirectionsStatus::MaxWaypointsExceeded => Err(GMapsClientError::MaxWaypointsExceeded),
+            DirectionsStatus::MaxRouteLengthExceeded => Err(GMapsClientError::MaxRouteLengthExceeded),
+            DirectionsStatus::InvalidRequest => Err(GMapsClientError::InvalidRequest),
+            DirectionsStatus::OverQueryLimit => Err(GMapsClientError::OverQueryLimit),
+            DirectionsStatus::OverDailyLimit | DirectionsStatus::RequestDenied => {
+                Err(GMapsClientError::RequestDenied {
+                    error_message: error_message.clone(),
+                })
+            }
+            DirectionsStatus::UnknownError => Err(GMapsClientError::UnknownError),
+        }
+    }
+}
+
+/// Origin, destination or waypoint of a route
+#[derive(Debug, Clone, PartialEq)]
+pub enum Location {
+    Address(String),
+    PlaceId(String),
+    LatLng(LatLng),
+}
+
+/// Formats the location the way the directions api expects it
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Location::Address(address) => write!(f, "{}", address),
+            Location::PlaceId(place_id) => write!(f, "place_id:{}", place_id),
+            Location::LatLng(lat_lng) => write!(f, "{}", lat_lng),
+        }
+    }
+}
+
+impl From<&str> for Location {
+    fn from(address: &str) -> Location {
+        Location::Address(address.to_string())
+    }
+}
+
+impl From<String> for Location {
+    fn from(address: String) -> Location {
+        Location::Address(address)
+    }
+}
+
+impl From<LatLng> for Location {
+    fn from(lat_lng: LatLng) -> Location {
+        Location::LatLng(lat_lng)
+    }
+}
+
+/// Means of transport used when computing a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TravelMode {
+    Driving,
+    Walking,
+    Bicycling,
+    Transit,
+}
+
+impl TravelMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TravelMode::Driving => "driving",
+            TravelMode::Walking => "walking",
+            TravelMode::Bicycling => "bicycling",
+            TravelMode::Transit => "transit",
+        }
+    }
+}
+
+/// Features a route should avoid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Avoid {
+    Tolls,
+    Highways,
+    Ferries,
+}
+
+impl Avoid {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Avoid::Tolls => "tolls",
+            Avoid::Highways => "highways",
+            Avoid::Ferries => "ferries",
+        }
+    }
+}
+
+/// Unit system used for the human readable distances
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Units {
+    Metric,
+    Imperial,
+}
+
+impl Units {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+        }
+    }
+}
+
+/// Desired departure time of a route
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepartureTime {
+    Now,
+    At(SystemTime),
+}
+
+pub(crate) fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+/// Encoded polyline approximating a path
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Polyline {
+    pub points: String,
+}
+
+/// A single instruction of a leg, such as a turn
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Step {
+    #[serde(default)]
+    pub html_instructions: String,
+
+    pub distance: Distance,
+
+    pub duration: TravelDuration,
+
+    pub start_location: LatLng,
+
+    pub end_location: LatLng,
+
+    pub polyline: Polyline,
+
+    pub travel_mode: TravelMode,
+
+    #[serde(default)]
+    pub maneuver: Option<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Step {
+
+    /// Decodes the polyline of the step into the points it passes through
+    /// 
+    /// returns: Result<Vec<LatLng>, GMapsClientError>
+    pub fn path(&self) -> Result<Vec<LatLng>, GMapsClientError> {
+        polyline::decode(&self.polyline.points, polyline::DEFAULT_PRECISION)
+    }
+}
+
+/// Part of a route between two consecutive waypoints
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leg {
+    #[serde(default)]
+    pub distance: Option<Distance>,
+
+    #[serde(default)]
+    pub duration: Option<TravelDuration>,
+
+    #[serde(default)]
+    pub duration_in_traffic: Option<TravelDuration>,
+
+    #[serde(default)]
+    pub start_address: String,
+
+    #[serde(default)]
+    pub end_address: String,
+
+    pub start_location: LatLng,
+
+    pub end_location: LatLng,
+
+    #[serde(default)]
+    pub steps: Vec<Step>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// A route from the origin to the destination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Route {
+    #[serde(default)]
+    pub summary: String,
+
+    #[serde(default)]
+    pub legs: Vec<Leg>,
+
+    pub bounds: Bounds,
+
+    pub overview_polyline: Polyline,
+
+    #[serde(default)]
+    pub warnings: Vec<String>,
+
+    #[serde(default)]
+    pub waypoint_order: Vec<usize>,
+
+    #[serde(default)]
+    pub copyrights: String,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Route {
+
+    /// Decodes the overview polyline of the route into an approximate path
+    /// 
+    /// returns: Result<Vec<LatLng>, GMapsClientError>
+    pub fn path(&self) -> Result<Vec<LatLng>, GMapsClientError> {
+        polyline::decode(&self.overview_polyline.points, polyline::DEFAULT_PRECISION)
+    }
+}
+
+/// Response of the directions request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DirectionsResponse {
+    #[serde(default)]
+    pub routes: Vec<Route>,
+
+    pub status: DirectionsStatus,
+
+    #[serde(default)]
+    pub error_message: Option<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ApiResponse for DirectionsResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+}
+
+/// Builder for a directions request, obtained from GMapsClient::directions
+#[derive(Debug)]
+pub struct DirectionsRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    origin: Location,
+    destination: Location,
+    waypoints: Vec<Location>,
+    mode: Option<TravelMode>,
+    avoid: Vec<Avoid>,
+    alternatives: bool,
+    units: Option<Units>,
+    departure_time: Option<DepartureTime>,
+    arrival_time: Option<SystemTime>,
+}
+
+impl<'a> DirectionsRequest<'a> {
+
+    /// Adds an intermediate location the route must pass through
+    pub fn waypoint(mut self, waypoint: impl Into<Location>) -> Self {
+        self.waypoints.push(waypoint.into());
+        self
+    }
+
+    /// Adds several intermediate locations, in the order they should be visited
+    pub fn waypoints<I, L>(mut self, waypoints: I) -> Self
+    where
+        I: IntoIterator<Item = L>,
+        L: Into<Location>,
+    {
+        self.waypoints.extend(waypoints.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn mode(mut self, mode: TravelMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Adds a feature the route should avoid, can be called multiple times
+    pub fn avoid(mut self, avoid: Avoid) -> Self {
+        if !self.avoid.contains(&avoid) {
+            self.avoid.push(avoid);
+        }
+        self
+    }
+
+    /// Asks the api for more than one route when available
+    pub fn alternatives(mut self, alternatives: bool) -> Self {
+        self.alternatives = alternatives;
+        self
+    }
+
+    /// Overrides the default units of the client
+    pub fn units(mut self, units: Units) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Sets the departure time, mutually exclusive with the arrival time
+    pub fn departure_time(mut self, departure_time: DepartureTime) -> Self {
+        self.departure_time = Some(departure_time);
+        self.arrival_time = None;
+        self
+    }
+
+    /// Sets the arrival time, mutually exclusive with the departure time
+    pub fn arrival_time(mut self, arrival_time: SystemTime) -> Self {
+        self.arrival_time = Some(arrival_time);
+        self.departure_time = None;
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("origin", self.origin.to_string()),
+            ("destination", self.destination.to_string()),
+        ];
+
+        if !self.waypoints.is_empty() {
+            let waypoints: Vec<String> = self.waypoints.iter().map(|w| w.to_string()).collect();
+            params.push(("waypoints", waypoints.join("|")));
+        }
+
+        if let Some(mode) = self.mode {
+            params.push(("mode", mode.as_str().to_string()));
+        }
+
+        if !self.avoid.is_empty() {
+            let avoid: Vec<&str> = self.avoid.iter().map(|a| a.as_str()).collect();
+            params.push(("avoid", avoid.join("|")));
+        }
+
+        if self.alternatives {
+            params.push(("alternatives", "true".to_string()));
+        }
+
+        if let Some(units) = self.units.or(self.client.units) {
+            params.push(("units", units.as_str().to_string()));
+        }
+
+        match self.departure_time {
+            Some(DepartureTime::Now) => params.push(("departure_time", "now".to_string())),
+            Some(DepartureTime::At(time)) => {
+                params.push(("departure_time", unix_seconds(time).to_string()))
+            }
+            None => {}
+        }
+
+        if let Some(time) = self.arrival_time {
+            params.push(("arrival_time", unix_seconds(time).to_string()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the directions api
+    /// 
+    /// returns: Result<DirectionsResponse, GMapsClientError>
+    pub async fn send(self) -> Result<DirectionsResponse, GMapsClientError> {
+
+        let response: DirectionsResponse = self
+            .client
+            .get_api(Api::Directions, "maps/api/directions/json", self.params())
+            .await?;
+
+        Ok(response)
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a directions request between an origin and a destination
+    /// 
+    /// parameters:
+    ///     * origin: Address, place id or coordinates the route starts from
+    ///     * destination: Address, place id or coordinates the route ends at
+    /// returns: DirectionsRequest, sent with DirectionsRequest::send
+    pub fn directions(
+        &self,
+        origin: impl Into<Location>,
+        destination: impl Into<Location>,
+    ) -> DirectionsRequest<'_> {
+        DirectionsRequest {
+            client: self,
+            origin: origin.into(),
+            destination: destination.into(),
+            waypoints: Vec::new(),
+            mode: None,
+            avoid: Vec::new(),
+            alternatives: false,
+            units: None,
+            departure_time: None,
+            arrival_time: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+    use std::time::Duration;
+
+    #[test]
+    pub fn test_directions_params() {
+        let client = test_client(None);
+        let request = client
+            .directions("Alba Iulia", Location::PlaceId("ChIJ123".to_string()))
+            .waypoint(LatLng::new(46.5, 23.5))
+            .waypoint("Sebes")
+            .mode(TravelMode::Driving)
+            .avoid(Avoid::Tolls)
+            .avoid(Avoid::Ferries)
+            .alternatives(true)
+            .units(Units::Metric)
+            .departure_time(DepartureTime::At(UNIX_EPOCH + Duration::from_secs(1700000000)));
+
+        let params = request.params();
+        assert_eq!(
+            params,
+            vec![
+                ("origin", "Alba Iulia".to_string()),
+                ("destination", "place_id:ChIJ123".to_string()),
+                ("waypoints", "46.5,23.5|Sebes".to_string()),
+                ("mode", "driving".to_string()),
+                ("avoid", "tolls|ferries".to_string()),
+                ("alternatives", "true".to_string()),
+                ("units", "metric".to_string()),
+                ("departure_time", "1700000000".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_deserialize_directions_response() {
+        let body = r#"{
+            "geocoded_waypoints": [],
+            "routes": [{
+                "summary": "DN1",
+                "bounds": {
+                    "northeast": { "lat": 46.8, "lng": 23.6 },
+                    "southwest": { "lat": 46.0, "lng": 23.5 }
+                },
+                "overview_polyline": { "points": "_p~iF~ps|U_ulLnnqC_mqNvxq`@" },
+                "legs": [{
+                    "distance": { "text": "95.3 km", "value": 95300 },
+                    "duration": { "text": "1 hour 15 mins", "value": 4500 },
+                    "start_address": "Alba Iulia, Romania",
+                    "end_address": "Cluj-Napoca, Romania",
+                    "start_location": { "lat": 46.0, "lng": 23.5 },
+                    "end_location": { "lat": 46.8, "lng": 23.6 },
+                    "steps": [{
+                        "html_instructions": "Head <b>north</b>",
+                        "distance": { "text": "95.3 km", "value": 95300 },
+                        "duration": { "text": "1 hour 15 mins", "value": 4500 },
+                        "start_location": { "lat": 46.0, "lng": 23.5 },
+                        "end_location": { "lat": 46.8, "lng": 23.6 },
+                        "polyline": { "points": "_p~iF~ps|U" },
+                        "travel_mode": "DRIVING"
+                    }]
+                }],
+                "warnings": [],
+                "waypoint_order": []
+            }],
+            "status": "OK"
+        }"#;
+
+        let response: DirectionsResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.status, DirectionsStatus::Ok);
+        assert!(response.extra.contains_key("geocoded_waypoints"));
+
+        let route = &response.routes[0];
+        assert_eq!(route.summary, "DN1");
+        assert_eq!(route.legs[0].distance.as_ref().unwrap().value, 95300);
+        assert_eq!(route.legs[0].steps[0].travel_mode, TravelMode::Driving);
+        assert_eq!(route.path().unwrap().len(), 3);
+        assert_eq!(route.legs[0].steps[0].path().unwrap(), vec![LatLng::new(38.5, -120.2)]);
+    }
+}
@@ -0,0 +1,458 @@
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use std::ops::{Index, Range};
+use std::time::SystemTime;
+
+use crate::directions::{unix_seconds, Avoid, DepartureTime, Location, TravelMode, Units};
+use crate::types::{Distance, TravelDuration};
+use crate::{Api, ApiResponse, GMapsClient, GMapsClientError, Validated};
+
+/// Maximum number of origins or destinations in a single request
+const MAX_DIMENSION: usize = 25;
+
+/// Maximum number of origin and destination pairs in a single request
+const MAX_ELEMENTS: usize = 100;
+
+/// Number of chunks sent at the same time when none is configured
+const DEFAULT_MAX_CONCURRENCY: usize = 4;
+
+/// Status codes returned by the distance matrix api for the whole request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DistanceMatrixStatus {
+    Ok,
+    InvalidRequest,
+    MaxElementsExceeded,
+    MaxDimensionsExceeded,
+    OverDailyLimit,
+    OverQueryLimit,
+    RequestDenied,
+    UnknownError,
+}
+
+impl DistanceMatrixStatus {
+
+    /// Maps every status other than OK into the matching GMapsClientError
+    pub(crate) fn check(self, error_message: &Option<String>) -> Result<(), GMapsClientError> {
+        match self {
+            DistanceMatrixStatus::Ok => Ok(()),
+            DistanceMatrixStatus::InvalidRequest
+            | DistanceMatrixStatus::MaxElementsExceeded
+            | DistanceMatrixStatus::MaxDimensionsExceeded => Err(GMapsClientError::InvalidRequest),
+            DistanceMatrixStatus::OverQueryLimit => Err(GMapsClientError::OverQueryLimit),
+            DistanceMatrixStatus::OverDailyLimit | DistanceMatrixStatus::RequestDenied => {
+                Err(GMapsClientError::RequestDenied {
+                    error_message: error_message.clone(),
+                })
+            }
+            DistanceMatrixStatus::UnknownError => Err(GMapsClientError::UnknownError),
+        }
+    }
+}
+
+/// Status of a single origin and destination pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ElementStatus {
+    Ok,
+    NotFound,
+    ZeroResults,
+    MaxRouteLengthExceeded,
+}
+
+/// Travel information between one origin and one destination
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MatrixElement {
+    pub status: ElementStatus,
+
+    #[serde(default)]
+    pub duration: Option<TravelDuration>,
+
+    #[serde(default)]
+    pub duration_in_traffic: Option<TravelDuration>,
+
+    #[serde(default)]
+    pub distance: Option<Distance>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct MatrixRow {
+    elements: Vec<MatrixElement>,
+}
+
+/// Response of a single distance matrix request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct DistanceMatrixResponse {
+    #[serde(default)]
+    origin_addresses: Vec<String>,
+
+    #[serde(default)]
+    destination_addresses: Vec<String>,
+
+    #[serde(default)]
+    rows: Vec<MatrixRow>,
+
+    status: DistanceMatrixStatus,
+
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+impl ApiResponse for DistanceMatrixResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+}
+
+/// Travel information for every origin and destination pair, indexed by (origin_idx, destination_idx)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matrix {
+    pub origin_addresses: Vec<String>,
+    pub destination_addresses: Vec<String>,
+    elements: Vec<MatrixElement>,
+}
+
+impl Matrix {
+
+    /// Returns the element for the given pair, None if any index is out of bounds
+    pub fn get(&self, origin_idx: usize, destination_idx: usize) -> Option<&MatrixElement> {
+        if destination_idx >= self.destination_addresses.len() {
+            return None;
+        }
+        self.elements
+            .get(origin_idx * self.destination_addresses.len() + destination_idx)
+    }
+
+    /// Iterates over the elements computed from the given origin, in destination order
+    pub fn row(&self, origin_idx: usize) -> impl Iterator<Item = &MatrixElement> {
+        let len = self.destination_addresses.len();
+        self.elements.iter().skip(origin_idx * len).take(len)
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = MatrixElement;
+
+    fn index(&self, (origin_idx, destination_idx): (usize, usize)) -> &MatrixElement {
+        self.get(origin_idx, destination_idx)
+            .expect("matrix index out of bounds")
+    }
+}
+
+/// Part of the matrix that fits in a single request
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Chunk {
+    origins: Range<usize>,
+    destinations: Range<usize>,
+}
+
+/// Splits the matrix into chunks respecting the per request limits of the api
+fn plan_chunks(origins_len: usize, destinations_len: usize) -> Vec<Chunk> {
+    let destinations_step = destinations_len.min(MAX_DIMENSION);
+    let origins_step = (MAX_ELEMENTS / destinations_step).min(MAX_DIMENSION);
+
+    let mut chunks = Vec::new();
+    for origins_start in (0..origins_len).step_by(origins_step) {
+        for destinations_start in (0..destinations_len).step_by(destinations_step) {
+            chunks.push(Chunk {
+                origins: origins_start..(origins_start + origins_step).min(origins_len),
+                destinations: destinations_start
+                    ..(destinations_start + destinations_step).min(destinations_len),
+            });
+        }
+    }
+
+    chunks
+}
+
+/// Stitches the chunk responses back into a single matrix
+fn assemble(
+    origins_len: usize,
+    destinations_len: usize,
+    responses: Vec<(Chunk, DistanceMatrixResponse)>,
+) -> Result<Matrix, GMapsClientError> {
+    let mut origin_addresses = vec![String::new(); origins_len];
+    let mut destination_addresses = vec![String::new(); destinations_len];
+    let mut elements: Vec<Option<MatrixElement>> = vec![None; origins_len * destinations_len];
+
+    for (chunk, response) in responses {
+        for (idx, address) in chunk.origins.clone().zip(response.origin_addresses) {
+            origin_addresses[idx] = address;
+        }
+        for (idx, address) in chunk.destinations.clone().zip(response.destination_addresses) {
+            destination_addresses[idx] = address;
+        }
+        for (origin_idx, row) in chunk.origins.clone().zip(response.rows) {
+            for (destination_idx, element) in chunk.destinations.clone().zip(row.elements) {
+                elements[origin_idx * destinations_len + destination_idx] = Some(element);
+            }
+        }
+    }
+
+    let elements = elements
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or(GMapsClientError::UnknownError)?;
+
+    Ok(Matrix {
+        origin_addresses,
+        destination_addresses,
+        elements,
+    })
+}
+
+/// Builder for a distance matrix request, obtained from GMapsClient::distance_matrix
+#[derive(Debug)]
+pub struct DistanceMatrixRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    origins: Vec<Location>,
+    destinations: Vec<Location>,
+    mode: Option<TravelMode>,
+    avoid: Vec<Avoid>,
+    units: Option<Units>,
+    departure_time: Option<DepartureTime>,
+    arrival_time: Option<SystemTime>,
+    max_concurrency: usize,
+}
+
+impl<'a> DistanceMatrixRequest<'a> {
+
+    pub fn mode(mut self, mode: TravelMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Adds a feature the routes should avoid, can be called multiple times
+    pub fn avoid(mut self, avoid: Avoid) -> Self {
+        if !self.avoid.contains(&avoid) {
+            self.avoid.push(avoid);
+        }
+        self
+    }
+
+    /// Overrides the default units of the client
+    pub fn units(mut self, units: Units) -> Self {
+        self.units = Some(units);
+        self
+    }
+
+    /// Sets the departure time, mutually exclusive with the arrival time.
+    /// Required for duration_in_traffic to be returned
+    pub fn departure_time(mut self, departure_time: DepartureTime) -> Self {
+        self.departure_time = Some(departure_time);
+        self.arrival_time = None;
+        self
+    }
+
+    /// Sets the arrival time, mutually exclusive with the departure time
+    pub fn arrival_time(mut self, arrival_time: SystemTime) -> Self {
+        self.arrival_time = Some(arrival_time);
+        self.departure_time = None;
+        self
+    }
+
+    /// Sets how many chunks of a large matrix are requested at the same time
+    pub fn max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    fn params(&self, chunk: &Chunk) -> Vec<(&'static str, String)> {
+        let join = |locations: &[Location]| {
+            locations.iter().map(|l| l.to_string()).collect::<Vec<_>>().join("|")
+        };
+
+        let mut params = vec![
+            ("origins", join(&self.origins[chunk.origins.clone()])),
+            ("destinations", join(&self.destinations[chunk.destinations.clone()])),
+        ];
+
+        if let Some(mode) = self.mode {
+            params.push(("mode", mode.as_str().to_string()));
+        }
+
+        if !self.avoid.is_empty() {
+            let avoid: Vec<&str> = self.avoid.iter().map(|a| a.as_str()).collect();
+            params.push(("avoid", avoid.join("|")));
+        }
+
+        if let Some(units) = self.units.or(self.client.units) {
+            params.push(("units", units.as_str().to_string()));
+        }
+
+        match self.departure_time {
+            Some(DepartureTime::Now) => params.push(("departure_time", "now".to_string())),
+            Some(DepartureTime::At(time)) => {
+                params.push(("departure_time", unix_seconds(time).to_string()))
+            }
+            None => {}
+        }
+
+        if let Some(time) = self.arrival_time {
+            params.push(("arrival_time", unix_seconds(time).to_string()));
+        }
+
+        params
+    }
+
+    async fn send_chunk(&self, chunk: Chunk) -> Result<(Chunk, DistanceMatrixResponse), GMapsClientError> {
+        let response: DistanceMatrixResponse = self
+            .client
+            .get_api(Api::DistanceMatrix, "maps/api/distancematrix/json", self.params(&chunk))
+            .await?;
+
+        Ok((chunk, response))
+    }
+
+    /// Sends the request to the distance matrix api, split in as many requests as
+    /// the api limits require
+    /// 
+    /// returns: Result<Matrix, GMapsClientError>
+    pub async fn send(self) -> Result<Matrix, GMapsClientError> {
+
+        if self.origins.is_empty() || self.destinations.is_empty() {
+            return Err(GMapsClientError::InvalidRequest);
+        }
+
+        let chunks = plan_chunks(self.origins.len(), self.destinations.len());
+        let responses = stream::iter(chunks)
+            .map(|chunk| self.send_chunk(chunk))
+            .buffer_unordered(self.max_concurrency)
+            .try_collect()
+            .await?;
+
+        assemble(self.origins.len(), self.destinations.len(), responses)
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a request computing the travel distance and time between every origin and destination
+    /// 
+    /// parameters:
+    ///     * origins: Addresses, place ids or coordinates to start from
+    ///     * destinations: Addresses, place ids or coordinates to end at
+    /// returns: DistanceMatrixRequest, sent with DistanceMatrixRequest::send
+    pub fn distance_matrix<O, D>(&self, origins: O, destinations: D) -> DistanceMatrixRequest<'_>
+    where
+        O: IntoIterator,
+        O::Item: Into<Location>,
+        D: IntoIterator,
+        D::Item: Into<Location>,
+    {
+        DistanceMatrixRequest {
+            client: self,
+            origins: origins.into_iter().map(Into::into).collect(),
+            destinations: destinations.into_iter().map(Into::into).collect(),
+            mode: None,
+            avoid: Vec::new(),
+            units: None,
+            departure_time: None,
+            arrival_time: None,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+
+    #[test]
+    pub fn test_plan_chunks_respects_limits() {
+        for (origins_len, destinations_len) in [(1, 1), (30, 7), (7, 30), (25, 25), (101, 3), (60, 60)] {
+            let chunks = plan_chunks(origins_len, destinations_len);
+
+            let mut covered = vec![0; origins_len * destinations_len];
+            for chunk in &chunks {
+                assert!(chunk.origins.len() <= MAX_DIMENSION);
+                assert!(chunk.destinations.len() <= MAX_DIMENSION);
+                assert!(chunk.origins.len() * chunk.destinations.len() <= MAX_ELEMENTS);
+
+                for origin_idx in chunk.origins.clone() {
+                    for destination_idx in chunk.destinations.clone() {
+                        covered[origin_idx * destinations_len + destination_idx] += 1;
+                    }
+                }
+            }
+            assert!(covered.iter().all(|&count| count == 1));
+        }
+    }
+
+    fn element(seconds: u64) -> MatrixElement {
+        MatrixElement {
+            status: ElementStatus::Ok,
+            duration: Some(TravelDuration { text: format!("{} s", seconds), value: seconds }),
+            duration_in_traffic: None,
+            distance: None,
+            extra: Map::new(),
+        }
+    }
+
+    fn response(chunk: &Chunk) -> DistanceMatrixResponse {
+        DistanceMatrixResponse {
+            origin_addresses: chunk.origins.clone().map(|o| format!("origin {}", o)).collect(),
+            destination_addresses: chunk.destinations.clone().map(|d| format!("destination {}", d)).collect(),
+            rows: chunk
+                .origins
+                .clone()
+                .map(|o| MatrixRow {
+                    elements: chunk.destinations.clone().map(|d| element((o * 100 + d) as u64)).collect(),
+                })
+                .collect(),
+            status: DistanceMatrixStatus::Ok,
+            error_message: None,
+        }
+    }
+
+    #[test]
+    pub fn test_assemble_chunks() {
+        let chunks = plan_chunks(30, 7);
+        let responses = chunks.iter().rev().map(|chunk| (chunk.clone(), response(chunk))).collect();
+
+        let matrix = assemble(30, 7, responses).unwrap();
+        assert_eq!(matrix.origin_addresses[29], "origin 29");
+        assert_eq!(matrix.destination_addresses[6], "destination 6");
+        assert_eq!(matrix[(17, 4)].duration.as_ref().unwrap().value, 1704);
+        assert_eq!(matrix.row(29).count(), 7);
+        assert!(matrix.get(30, 0).is_none());
+        assert!(matrix.get(0, 7).is_none());
+    }
+
+    #[test]
+    pub fn test_assemble_fails_on_missing_elements() {
+        let chunks = plan_chunks(30, 7);
+        let responses = chunks.iter().skip(1).map(|chunk| (chunk.clone(), response(chunk))).collect();
+
+        assert!(assemble(30, 7, responses).is_err());
+    }
+
+    #[test]
+    pub fn test_deserialize_distance_matrix_response() {
+        let body = r#"{
+            "destination_addresses": ["Cluj-Napoca, Romania"],
+            "origin_addresses": ["Alba Iulia, Romania"],
+            "rows": [{
+                "elements": [{
+                    "distance": { "text": "95.3 km", "value": 95300 },
+                    "duration": { "text": "1 hour 15 mins", "value": 4500 },
+                    "duration_in_traffic": { "text": "1 hour 20 mins", "value": 4800 },
+                    "status": "OK"
+                }]
+            }],
+            "status": "OK"
+        }"#;
+
+        let response: DistanceMatrixResponse = serde_json::from_str(body).unwrap();
+        let element = &response.rows[0].elements[0];
+        assert_eq!(element.distance.as_ref().unwrap().value, 95300);
+        assert_eq!(element.duration_in_traffic.as_ref().unwrap().value, 4800);
+    }
+}
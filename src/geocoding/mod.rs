@@ -0,0 +1,393 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::types::{Bounds, LatLng, Viewport};
+use crate::{Api, ApiResponse, GMapsClient, GMapsClientError, Validated};
+
+/// Status codes returned by the geocoding api
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum GeocodingStatus {
+    Ok,
+    ZeroResults,
+    OverDailyLimit,
+    OverQueryLimit,
+    RequestDenied,
+    InvalidRequest,
+    UnknownError,
+}
+
+impl GeocodingStatus {
+
+    /// Maps every status other than OK into the matching GMapsClientError
+    pub(crate) fn check(self, error_message: &Option<String>) -> Result<(), GMapsClientError> {
+        match self {
+            GeocodingStatus::Ok => Ok(()),
+            GeocodingStatus::ZeroResults => Err(GMapsClientError::ZeroResults),
+            GeocodingStatus::OverQueryLimit => Err(GMapsClientError::OverQueryLimit),
+            GeocodingStatus::OverDailyLimit | GeocodingStatus::RequestDenied => {
+                Err(GMapsClientError::RequestDenied {
+                    error_message: error_message.clone(),
+                })
+            }
+            GeocodingStatus::InvalidRequest => Err(GMapsClientError::InvalidRequest),
+            GeocodingStatus::UnknownError => Err(GMapsClientError::UnknownError),
+        }
+    }
+}
+
+/// Restriction of the geocoding results to a given area
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Component {
+    Country(String),
+    PostalCode(String),
+    Locality(String),
+    AdministrativeArea(String),
+    Route(String),
+}
+
+impl Component {
+    fn to_param(&self) -> String {
+        match self {
+            Component::Country(country) => format!("country:{}", country),
+            Component::PostalCode(postal_code) => format!("postal_code:{}", postal_code),
+            Component::Locality(locality) => format!("locality:{}", locality),
+            Component::AdministrativeArea(area) => format!("administrative_area:{}", area),
+            Component::Route(route) => format!("route:{}", route),
+        }
+    }
+}
+
+/// Precision of a geocoded location
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum LocationType {
+    Rooftop,
+    RangeInterpolated,
+    GeometricCenter,
+    Approximate,
+}
+
+impl LocationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LocationType::Rooftop => "ROOFTOP",
+            LocationType::RangeInterpolated => "RANGE_INTERPOLATED",
+            LocationType::GeometricCenter => "GEOMETRIC_CENTER",
+            LocationType::Approximate => "APPROXIMATE",
+        }
+    }
+}
+
+/// A single part of an address, such as the street number or the city
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddressComponent {
+    pub long_name: String,
+
+    pub short_name: String,
+
+    #[serde(default)]
+    pub types: Vec<String>,
+}
+
+/// Location of a geocoding result along with its precision
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeocodingGeometry {
+    pub location: LatLng,
+
+    pub location_type: LocationType,
+
+    pub viewport: Viewport,
+
+    #[serde(default)]
+    pub bounds: Option<Bounds>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// An address and its location as returned by the geocoding api
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeocodingResult {
+    #[serde(default)]
+    pub address_components: Vec<AddressComponent>,
+
+    pub formatted_address: String,
+
+    pub geometry: GeocodingGeometry,
+
+    pub place_id: String,
+
+    #[serde(default)]
+    pub types: Vec<String>,
+
+    /// Set when the geocoder could only match part of the requested address
+    #[serde(default)]
+    pub partial_match: bool,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl GeocodingResult {
+
+    /// Returns the first address component of the given type, such as "locality" or "country"
+    pub fn component(&self, component_type: &str) -> Option<&AddressComponent> {
+        self.address_components
+            .iter()
+            .find(|component| component.types.iter().any(|t| t == component_type))
+    }
+}
+
+/// Response of the geocoding and reverse geocoding requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeocodingResponse {
+    #[serde(default)]
+    pub results: Vec<GeocodingResult>,
+
+    pub status: GeocodingStatus,
+
+    #[serde(default)]
+    pub error_message: Option<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ApiResponse for GeocodingResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+}
+
+/// Builder for a geocoding request, obtained from GMapsClient::geocode
+#[derive(Debug)]
+pub struct GeocodeRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    address: String,
+    components: Vec<Component>,
+    bounds: Option<Bounds>,
+    region: Option<String>,
+}
+
+impl<'a> GeocodeRequest<'a> {
+
+    /// Restricts the results to the given component, can be called multiple times
+    pub fn component(mut self, component: Component) -> Self {
+        self.components.push(component);
+        self
+    }
+
+    /// Prefers results located inside the given bounds
+    pub fn bounds(mut self, bounds: Bounds) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    /// Prefers results located in the given region, as a ccTLD code such as "ro"
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("address", self.address.clone())];
+
+        if !self.components.is_empty() {
+            let components: Vec<String> = self.components.iter().map(|c| c.to_param()).collect();
+            params.push(("components", components.join("|")));
+        }
+
+        if let Some(bounds) = self.bounds {
+            params.push(("bounds", format!("{}|{}", bounds.southwest, bounds.northeast)));
+        }
+
+        if let Some(region) = &self.region {
+            params.push(("region", region.clone()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the geocoding api
+    /// 
+    /// returns: Result<GeocodingResponse, GMapsClientError>
+    pub async fn send(self) -> Result<GeocodingResponse, GMapsClientError> {
+
+        let response: GeocodingResponse = self
+            .client
+            .get_api(Api::Geocoding, "maps/api/geocode/json", self.params())
+            .await?;
+
+        Ok(response)
+    }
+}
+
+/// Builder for a reverse geocoding request, obtained from GMapsClient::reverse_geocode
+#[derive(Debug)]
+pub struct ReverseGeocodeRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    location: LatLng,
+    result_types: Vec<String>,
+    location_types: Vec<LocationType>,
+}
+
+impl<'a> ReverseGeocodeRequest<'a> {
+
+    /// Keeps only the results of the given type, such as "street_address", can be called multiple times
+    pub fn result_type(mut self, result_type: &str) -> Self {
+        self.result_types.push(result_type.to_string());
+        self
+    }
+
+    /// Keeps only the results of the given precision, can be called multiple times
+    pub fn location_type(mut self, location_type: LocationType) -> Self {
+        if !self.location_types.contains(&location_type) {
+            self.location_types.push(location_type);
+        }
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("latlng", self.location.to_string())];
+
+        if !self.result_types.is_empty() {
+            params.push(("result_type", self.result_types.join("|")));
+        }
+
+        if !self.location_types.is_empty() {
+            let location_types: Vec<&str> = self.location_types.iter().map(|l| l.as_str()).collect();
+            params.push(("location_type", location_types.join("|")));
+        }
+
+        params
+    }
+
+    /// Sends the request to the geocoding api
+    /// 
+    /// returns: Result<GeocodingResponse, GMapsClientError>
+    pub async fn send(self) -> Result<GeocodingResponse, GMapsClientError> {
+
+        let response: GeocodingResponse = self
+            .client
+            .get_api(Api::Geocoding, "maps/api/geocode/json", self.params())
+            .await?;
+
+        Ok(response)
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a request converting an address into coordinates
+    /// 
+    /// parameters:
+    ///     * address: Street address or plus code to geocode
+    /// returns: GeocodeRequest, sent with GeocodeRequest::send
+    pub fn geocode(&self, address: &str) -> GeocodeRequest<'_> {
+        GeocodeRequest {
+            client: self,
+            address: address.to_string(),
+            components: Vec::new(),
+            bounds: None,
+            region: None,
+        }
+    }
+
+    /// Starts a request converting coordinates into the closest addresses
+    /// 
+    /// parameters:
+    ///     * location: Coordinates to look up
+    /// returns: ReverseGeocodeRequest, sent with ReverseGeocodeRequest::send
+    pub fn reverse_geocode(&self, location: LatLng) -> ReverseGeocodeRequest<'_> {
+        ReverseGeocodeRequest {
+            client: self,
+            location,
+            result_types: Vec::new(),
+            location_types: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+
+    #[test]
+    pub fn test_geocode_params() {
+        let client = test_client(None);
+        let request = client
+            .geocode("Piata Unirii")
+            .component(Component::Country("RO".to_string()))
+            .component(Component::Locality("Cluj-Napoca".to_string()))
+            .bounds(Bounds {
+                northeast: LatLng::new(46.8, 23.7),
+                southwest: LatLng::new(46.7, 23.5),
+            })
+            .region("ro");
+
+        assert_eq!(
+            request.params(),
+            vec![
+                ("address", "Piata Unirii".to_string()),
+                ("components", "country:RO|locality:Cluj-Napoca".to_string()),
+                ("bounds", "46.7,23.5|46.8,23.7".to_string()),
+                ("region", "ro".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_reverse_geocode_params() {
+        let client = test_client(None);
+        let request = client
+            .reverse_geocode(LatLng::new(46.07, 23.58))
+            .result_type("street_address")
+            .result_type("locality")
+            .location_type(LocationType::Rooftop);
+
+        assert_eq!(
+            request.params(),
+            vec![
+                ("latlng", "46.07,23.58".to_string()),
+                ("result_type", "street_address|locality".to_string()),
+                ("location_type", "ROOFTOP".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_deserialize_geocoding_response() {
+        let body = r#"{
+            "results": [{
+                "address_components": [
+                    { "long_name": "Alba Iulia", "short_name": "Alba Iulia", "types": ["locality", "political"] },
+                    { "long_name": "Romania", "short_name": "RO", "types": ["country", "political"] }
+                ],
+                "formatted_address": "Alba Iulia, Romania",
+                "geometry": {
+                    "location": { "lat": 46.07, "lng": 23.58 },
+                    "location_type": "APPROXIMATE",
+                    "viewport": {
+                        "northeast": { "lat": 46.1, "lng": 23.6 },
+                        "southwest": { "lat": 46.0, "lng": 23.5 }
+                    }
+                },
+                "place_id": "ChIJ123",
+                "types": ["locality", "political"]
+            }],
+            "status": "OK"
+        }"#;
+
+        let response: GeocodingResponse = serde_json::from_str(body).unwrap();
+        let result = &response.results[0];
+        assert_eq!(result.geometry.location_type, LocationType::Approximate);
+        assert_eq!(result.component("country").unwrap().short_name, "RO");
+        assert!(result.component("postal_code").is_none());
+    }
+}
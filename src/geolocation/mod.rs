@@ -0,0 +1,373 @@
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::query::QueryParams;
+use crate::types::LatLng;
+use crate::{decode_json, Api, GMapsClient, GMapsClientError, Validated};
+
+/// Radio technology of the cell towers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RadioType {
+    Gsm,
+    Cdma,
+    Wcdma,
+    Lte,
+    Nr,
+}
+
+/// A wifi access point visible to the device
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiAccessPoint {
+    pub mac_address: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal_strength: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal_to_noise_ratio: Option<i32>,
+}
+
+/// A cell tower visible to the device, identified by cell_id, or by new_radio_cell_id
+/// for the 5G NR cells whose identities do not fit in 32 bits
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CellTower {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell_id: Option<u32>,
+
+    /// 36 bit identity of a NR cell
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_radio_cell_id: Option<u64>,
+
+    pub location_area_code: u32,
+
+    pub mobile_country_code: u32,
+
+    pub mobile_network_code: u32,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub age: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signal_strength: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing_advance: Option<u32>,
+}
+
+/// Body of a geolocation request, every field is optional
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeolocationRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home_mobile_country_code: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub home_mobile_network_code: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub radio_type: Option<RadioType>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub carrier: Option<String>,
+
+    /// Whether to fall back to the ip address when the wifi and cell signals are not enough
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consider_ip: Option<bool>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cell_towers: Vec<CellTower>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub wifi_access_points: Vec<WifiAccessPoint>,
+}
+
+/// Estimated location of the device
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeolocationResponse {
+    pub location: LatLng,
+
+    /// Radius of the uncertainty circle around the location, in meters
+    pub accuracy: f64,
+}
+
+/// Error body returned by the geolocation api along with a non 2xx status
+#[derive(Debug, Deserialize)]
+struct GeolocationErrorResponse {
+    error: GeolocationError,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeolocationError {
+    #[serde(default)]
+    message: Option<String>,
+
+    #[serde(default)]
+    errors: Vec<GeolocationErrorDetail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeolocationErrorDetail {
+    reason: String,
+}
+
+impl GeolocationError {
+
+    /// Maps the reason of a 4xx error into the matching GMapsClientError. Unknown reasons
+    /// are mapped from the status, 403 meaning the request was denied, so that these
+    /// permanent failures are not retried
+    fn into_client_error(self, status: StatusCode) -> GMapsClientError {
+        let reason = self.errors.first().map(|detail| detail.reason.as_str());
+
+        match reason {
+            Some("notFound") => GMapsClientError::NotFound,
+            Some("dailyLimitExceeded") | Some("userRateLimitExceeded") => {
+                GMapsClientError::OverQueryLimit
+            }
+            _ if status == StatusCode::TOO_MANY_REQUESTS => GMapsClientError::OverQueryLimit,
+            Some("keyInvalid") => GMapsClientError::RequestDenied {
+                error_message: self.message,
+            },
+            _ if status == StatusCode::FORBIDDEN => GMapsClientError::RequestDenied {
+                error_message: self.message,
+            },
+            _ => GMapsClientError::InvalidRequest,
+        }
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Estimates the location of the device from the visible cell towers and wifi access points
+    /// 
+    /// parameters:
+    ///     * request: Signals visible to the device
+    /// returns: Result<GeolocationResponse, GMapsClientError>
+    pub async fn geolocate(
+        &self,
+        request: &GeolocationRequest,
+    ) -> Result<GeolocationResponse, GMapsClientError> {
+
+        let params = QueryParams::new();
+
+        self.with_retry(|| {
+            self.with_keys(Api::Geolocation, "geolocation/v1/geolocate", &params, |url| {
+                self.send_geolocate(url, request)
+            })
+        })
+        .await
+    }
+
+    async fn send_geolocate(
+        &self,
+        url: Url,
+        request: &GeolocationRequest,
+    ) -> Result<GeolocationResponse, GMapsClientError> {
+
+        self.acquire(Api::Geolocation).await?;
+
+        let response = self
+            .request(self.http.post(url).json(request))
+            .send()
+            .await
+            .map_err(GMapsClientError::request_failure)?;
+
+        let status = response.status();
+        if !status.is_success() && !status.is_server_error() {
+            let body = response.text().await.map_err(GMapsClientError::request_failure)?;
+            let error: GeolocationErrorResponse = decode_json(&body)?;
+            return Err(error.error.into_client_error(status));
+        }
+
+        let body = response
+            .error_for_status()
+            .map_err(GMapsClientError::request_failure)?
+            .text()
+            .await
+            .map_err(GMapsClientError::request_failure)?;
+
+        decode_json(&body)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    pub fn test_serialize_geolocation_request() {
+        let request = GeolocationRequest {
+            consider_ip: Some(false),
+            radio_type: Some(RadioType::Lte),
+            cell_towers: vec![CellTower {
+                cell_id: Some(42),
+                location_area_code: 415,
+                mobile_country_code: 226,
+                mobile_network_code: 10,
+                ..Default::default()
+            }],
+            wifi_access_points: vec![WifiAccessPoint {
+                mac_address: "01:23:45:67:89:ab".to_string(),
+                signal_strength: Some(-65),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            serde_json::json!({
+                "radioType": "lte",
+                "considerIp": false,
+                "cellTowers": [{
+                    "cellId": 42,
+                    "locationAreaCode": 415,
+                    "mobileCountryCode": 226,
+                    "mobileNetworkCode": 10
+                }],
+                "wifiAccessPoints": [{
+                    "macAddress": "01:23:45:67:89:ab",
+                    "signalStrength": -65
+                }]
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_serialize_new_radio_cell() {
+        let tower = CellTower {
+            new_radio_cell_id: Some(68_719_476_735),
+            location_area_code: 415,
+            mobile_country_code: 226,
+            mobile_network_code: 10,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            serde_json::to_value(&tower).unwrap(),
+            serde_json::json!({
+                "newRadioCellId": 68_719_476_735u64,
+                "locationAreaCode": 415,
+                "mobileCountryCode": 226,
+                "mobileNetworkCode": 10
+            })
+        );
+    }
+
+    #[test]
+    pub fn test_geolocation_error_mapping() {
+        let body = r#"{
+            "error": {
+                "errors": [{ "domain": "geolocation", "reason": "notFound", "message": "Not Found" }],
+                "code": 404,
+                "message": "Not Found"
+            }
+        }"#;
+
+        let error: GeolocationErrorResponse = serde_json::from_str(body).unwrap();
+        assert!(matches!(error.error.into_client_error(StatusCode::NOT_FOUND), GMapsClientError::NotFound));
+    }
+
+    #[test]
+    pub fn test_unknown_reasons_are_mapped_from_the_status() {
+        let error = |reason: &str| GeolocationError {
+            message: Some(reason.to_string()),
+            errors: vec![GeolocationErrorDetail { reason: reason.to_string() }],
+        };
+
+        assert!(matches!(
+            error("accessNotConfigured").into_client_error(StatusCode::FORBIDDEN),
+            GMapsClientError::RequestDenied { .. }
+        ));
+        assert!(matches!(
+            error("keyExpired").into_client_error(StatusCode::BAD_REQUEST),
+            GMapsClientError::InvalidRequest
+        ));
+        assert!(matches!(
+            error("dailyLimitExceeded").into_client_error(StatusCode::FORBIDDEN),
+            GMapsClientError::OverQueryLimit
+        ));
+    }
+
+    #[tokio::test]
+    pub async fn test_geolocate_posts_the_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/geolocation/v1/geolocate"))
+            .and(query_param("key", "test_key"))
+            .and(body_json(serde_json::json!({
+                "considerIp": false,
+                "wifiAccessPoints": [{ "macAddress": "01:23:45:67:89:ab" }]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "location": { "lat": 46.07, "lng": 23.58 },
+                "accuracy": 25.0
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = GMapsClient::builder()
+            .api_key("test_key")
+            .api_base_url(Api::Geolocation, &server.uri())
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        let request = GeolocationRequest {
+            consider_ip: Some(false),
+            wifi_access_points: vec![WifiAccessPoint {
+                mac_address: "01:23:45:67:89:ab".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let response = client.geolocate(&request).await.unwrap();
+        assert_eq!(response.location, LatLng::new(46.07, 23.58));
+    }
+
+    #[tokio::test]
+    pub async fn test_geolocate_denied_is_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+                "error": {
+                    "errors": [{ "domain": "usageLimits", "reason": "accessNotConfigured" }],
+                    "code": 403,
+                    "message": "Geolocation API has not been used in this project"
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(Some(&server.uri()));
+
+        assert!(matches!(
+            client.geolocate(&GeolocationRequest::default()).await,
+            Err(GMapsClientError::RequestDenied { .. })
+        ));
+    }
+
+    #[test]
+    pub fn test_deserialize_geolocation_response() {
+        let body = r#"{ "location": { "lat": 46.07, "lng": 23.58 }, "accuracy": 1200.5 }"#;
+
+        let response: GeolocationResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.location, LatLng::new(46.07, 23.58));
+        assert_eq!(response.accuracy, 1200.5);
+    }
+}
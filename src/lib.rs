@@ -1,20 +1,69 @@
-mod geolocation;
-
-use serde_json::json;
+mod auth;
+mod builder;
+pub mod cache;
+pub mod directions;
+pub mod distance_matrix;
+pub mod geocoding;
+pub mod geolocation;
+pub mod places;
+pub mod polyline;
+mod query;
+mod rate_limit;
+mod retry;
+pub mod types;
+
+pub use auth::{
+    ApiKeyProvider, Auth, ChainedApiKeyProvider, EnvApiKeyProvider, FileApiKeyProvider,
+    KeyRotation, SecretString, StaticApiKeyProvider,
+};
+pub use builder::GMapsClientBuilder;
+pub use cache::{Cache, MemoryCache};
+pub use directions::{
+    Avoid, DepartureTime, DirectionsRequest, DirectionsResponse, DirectionsStatus, Leg, Location,
+    Polyline, Route, Step, TravelMode, Units,
+};
+pub use distance_matrix::{
+    DistanceMatrixRequest, DistanceMatrixStatus, ElementStatus, Matrix, MatrixElement,
+};
+pub use geocoding::{
+    AddressComponent, Component, GeocodeRequest, GeocodingGeometry, GeocodingResponse,
+    GeocodingResult, GeocodingStatus, LocationType, ReverseGeocodeRequest,
+};
+pub use geolocation::{
+    CellTower, GeolocationRequest, GeolocationResponse, RadioType, WifiAccessPoint,
+};
+pub use places::{
+    AutocompleteRequest, AutocompleteResponse, AutocompleteSession, FindPlaceRequest,
+    FindPlaceResponse, LocationBias, NearbyRanking, NearbySearchRequest, NearbySearchResponse,
+    Photo, Place, PlaceDetails, PlaceDetailsRequest, PlaceDetailsResponse, PlaceField,
+    PlacePhoto, PlaceStatus, Prediction, QueryAutocompleteRequest, SessionToken,
+    TextSearchRequest, TextSearchResponse,
+};
+pub use rate_limit::RateLimit;
+pub use retry::{RetryCondition, RetryPolicy};
+pub use types::{Bounds, Distance, Geometry, LatLng, TravelDuration, Viewport};
+
+use reqwest::header::USER_AGENT;
+use reqwest::{RequestBuilder, Url};
+use serde::de::DeserializeOwned;
 use thiserror::Error;
 
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
 use std::marker::PhantomData;
-use std::env;
-use std::path::Path;
+use std::sync::Arc;
 
-use dotenv_loader::parser::Parser;
+use auth::KeyPool;
+use query::QueryParams;
+use rate_limit::RateLimiter;
 
 /// Unit struct that protects the client from invalid api key users
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Validated;
 
 /// Unit struct that protects the client from invalid api key users
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Invalidated;
 
 #[derive(Error, Debug)]
@@ -22,41 +71,158 @@ pub enum GMapsClientError {
     #[error("Failed to validate API KEY")]
     InvalidApiKey,
     
-    #[error("Failed to load the Google Maps API KEY")]
-    ApiKeyLoadingFailure,
+    #[error("Failed to read the Google Maps API KEY from {source_name}")]
+    ApiKeyLoadingFailure {
+        source_name: String,
+        #[source]
+        source: std::io::Error,
+    },
 
     #[error("Failed sending the request")]
-    RequestFailure,
+    RequestFailure(#[source] reqwest::Error),
     
-    #[error("Missing API KEY, the GMAPS_API_KEY variable may not be set")]
-    MissingApiKey,
+    #[error("Missing API KEY, none was found in {0}")]
+    MissingApiKey(String),
 
-    
+    #[error("Failed to decode the response: {snippet}")]
+    Decode {
+        snippet: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[error("The request returned no results")]
+    ZeroResults,
+
+    #[error("The query limit of the API KEY was exceeded")]
+    OverQueryLimit,
+
+    #[error("The request was denied: {}", error_message.as_deref().unwrap_or("no reason given"))]
+    RequestDenied { error_message: Option<String> },
+
+    #[error("The request was invalid, a required parameter may be missing")]
+    InvalidRequest,
+
+    #[error("The referenced location or place was not found")]
+    NotFound,
+
+    #[error("The server failed to process the request")]
+    UnknownError,
+
+    #[error("Too many waypoints were provided in the request")]
+    MaxWaypointsExceeded,
+
+    #[error("The requested route is too long to be processed")]
+    MaxRouteLengthExceeded,
+
+    #[error("Failed to decode the polyline {0}")]
+    InvalidPolyline(String),
+
+    #[error("The point {0} cannot be encoded in a polyline")]
+    InvalidCoordinates(LatLng),
+
+    #[error("The signing secret is not valid url-safe base64")]
+    InvalidSigningSecret,
+
+    #[error("Invalid rate limit of {0} requests per second, it must be finite, above zero and allow a request within the range of a Duration")]
+    InvalidRateLimit(f64),
+
+    #[error("Invalid base url {0}")]
+    InvalidBaseUrl(String),
+
+    #[error("The rate limit of the {api:?} api was reached")]
+    RateLimited { api: Api },
+
+    #[cfg(feature = "sqlite-cache")]
+    #[error("The sqlite cache failed")]
+    SqliteCache(#[from] rusqlite::Error),
+
+    #[error("Failed writing the response")]
+    Io(#[from] std::io::Error),
+
+    #[error("The request kept failing after {attempts} attempts")]
+    RetriesExhausted {
+        attempts: u32,
+        #[source]
+        source: Box<GMapsClientError>,
+    },
+}
+
+impl GMapsClientError {
+
+    /// Wraps a reqwest error, redacting the credentials from the url it carries
+    pub(crate) fn request_failure(mut error: reqwest::Error) -> GMapsClientError {
+        if let Some(url) = error.url_mut() {
+            query::redact_url(url);
+        }
+        GMapsClientError::RequestFailure(error)
+    }
+
+    /// Whether the quota ran out, possibly after the retries were exhausted
+    fn is_over_query_limit(&self) -> bool {
+        match self {
+            GMapsClientError::OverQueryLimit => true,
+            GMapsClientError::RetriesExhausted { source, .. } => source.is_over_query_limit(),
+            _ => false,
+        }
+    }
+}
+
+/// Number of characters of an undecodable body or polyline kept in GMapsClientError::Decode
+/// and GMapsClientError::InvalidPolyline
+pub(crate) const DECODE_SNIPPET_LEN: usize = 256;
+
+/// Families of google maps apis, each of them with its own base url and rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Api {
+    Places,
+    Directions,
+    Geocoding,
+    DistanceMatrix,
+    Geolocation,
 }
 
-#[derive(Debug)]
+impl Api {
+
+    pub const ALL: [Api; 5] = [
+        Api::Places,
+        Api::Directions,
+        Api::Geocoding,
+        Api::DistanceMatrix,
+        Api::Geolocation,
+    ];
+
+    /// Base url google serves the api from
+    pub fn default_base_url(&self) -> &'static str {
+        match self {
+            Api::Geolocation => "https://www.googleapis.com",
+            _ => "https://maps.googleapis.com",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct GMapsClient<T = Invalidated> {
-    api_key: String,
-    base_url: String,
+    keys: Arc<KeyPool>,
+    base_urls: HashMap<Api, String>,
+    http: reqwest::Client,
+    user_agent: String,
+    language: Option<String>,
+    region: Option<String>,
+    units: Option<Units>,
+    timeout: Option<std::time::Duration>,
+    retry_policy: RetryPolicy,
+    rate_limiters: Arc<HashMap<Api, RateLimiter>>,
+    cache: Option<Arc<dyn Cache>>,
+    cache_ttl: std::time::Duration,
     state: PhantomData<T>,
 }
 
 impl GMapsClient<Invalidated> {
 
-    pub fn load_api_key() -> Result<String, GMapsClientError> {
-        
-        let mut dotenv_parser = Parser::new();
-        let _res = dotenv_parser.parse(Path::new(".env"));
-
-        if let Ok(mut api_key) = env::var("GMAPS_API_KEY") {
-            if api_key.starts_with('"') && api_key.ends_with('"') {
-                api_key.remove(0);
-                api_key.remove(api_key.len()-1);
-            }
-            return Ok(api_key);
-        }
-
-        Err(GMapsClientError::ApiKeyLoadingFailure)
+    /// Loads the api key from the GMAPS_API_KEY variable, see EnvApiKeyProvider
+    pub fn load_api_key() -> Result<SecretString, GMapsClientError> {
+        EnvApiKeyProvider::default().api_key()
     }
 
     /// Constructs a GMapsClient object 
@@ -65,103 +231,286 @@ impl GMapsClient<Invalidated> {
     /// 
     /// returns: Result<GMapsClient, GmapsClientError>
     pub fn new() -> Result<GMapsClient<Invalidated>, GMapsClientError> {
+        GMapsClient::builder().build()
+    }
 
-        GMapsClient::load_api_key()?;
-
-        let api_key = GMapsClient::load_api_key()?;
-
-        Ok(GMapsClient {
-            api_key: api_key,
-            base_url: "https://maps.googleapis.com/".to_string(),
-            state: PhantomData
-        })
+    /// Starts the configuration of a GMapsClient, see GMapsClientBuilder
+    pub fn builder() -> GMapsClientBuilder {
+        GMapsClientBuilder::new()
     }
 
-    /// Validates the api key by calling the places api
-    /// If valid this function returns GmapsClient<Validated> which gives access
-    /// to the api, consuming self in the process 
+    /// Validates the api keys by calling the places api with each of them
+    /// If at least one is valid this function returns GmapsClient<Validated> which gives
+    /// access to the api, consuming self in the process. Keys out of quota are valid but
+    /// quarantined, as are the keys that could not be validated, whether denied or unreachable
     /// 
     /// returns: Result<GMapsClient<Validated>, GmapsClientError>
     pub async fn validate_api_key(self) -> Result<GMapsClient<Validated>, GMapsClientError> {
         
-        let base_url = "https://maps.googleapis.com/".to_string();
-
-        let url = format!("{}/maps/api/place/findplacefromtext/json?input={}&inputtype=textquery&fields=name,place_id,geometry,formatted_address&locationbias=point:50,10&key={}",
-            base_url, "bosfor alba", self.api_key);
-    
-        let response = 
-            reqwest::get(url)
-            .await
-            .map_err(|_| GMapsClientError::RequestFailure)?
-            .json::<serde_json::Value>()
-            .await
-            .map_err(|_| GMapsClientError::RequestFailure)?;
+        let path = "maps/api/place/findplacefromtext/json";
+        let params = self.with_defaults(vec![
+            ("input", "bosfor alba".to_string()),
+            ("inputtype", "textquery".to_string()),
+            ("fields", "place_id".to_string()),
+        ]);
+
+        let mut valid_keys = 0;
+        let mut last_error = None;
+        for index in 0..self.keys.len() {
+            let url = self.key_url(index, Api::Places, path, params.clone());
+            let response = self
+                .with_retry(|| self.get_response::<FindPlaceResponse>(Api::Places, url.clone()))
+                .await;
+
+            match response {
+                Ok(_) | Err(GMapsClientError::ZeroResults) => valid_keys += 1,
+                Err(error) if error.is_over_query_limit() => {
+                    self.keys.quarantine(index);
+                    valid_keys += 1;
+                }
+                Err(GMapsClientError::RequestDenied { .. }) => self.keys.quarantine(index),
+                Err(error) => {
+                    self.keys.quarantine(index);
+                    last_error = Some(error);
+                }
+            }
+        }
 
-        if response["status"] == json!("REQUEST_DENIED") {
-            return Err(GMapsClientError::InvalidApiKey);
+        match (valid_keys, last_error) {
+            (0, Some(error)) => Err(error),
+            (0, None) => Err(GMapsClientError::InvalidApiKey),
+            _ => Ok(self.into_state()),
         }
-        
-        Ok(GMapsClient {
-            api_key: self.api_key,
-            base_url: self.base_url,
+    }
+
+}
+
+impl<T> GMapsClient<T> {
+
+    /// Moves the configuration of the client into another typestate
+    fn into_state<U>(self) -> GMapsClient<U> {
+        GMapsClient {
+            keys: self.keys,
+            base_urls: self.base_urls,
+            http: self.http,
+            user_agent: self.user_agent,
+            language: self.language,
+            region: self.region,
+            units: self.units,
+            timeout: self.timeout,
+            retry_policy: self.retry_policy,
+            rate_limiters: self.rate_limiters,
+            cache: self.cache,
+            cache_ttl: self.cache_ttl,
             state: PhantomData,
-        })
+        }
+    }
 
+    /// Base url the given api is reached at, without a trailing slash
+    fn base_url(&self, api: Api) -> &str {
+        self.base_urls
+            .get(&api)
+            .map(String::as_str)
+            .unwrap_or_else(|| api.default_base_url())
     }
 
-}
+    /// Applies the per request configuration of the client to the given request
+    fn request(&self, request: RequestBuilder) -> RequestBuilder {
+        let request = request.header(USER_AGENT, &self.user_agent);
 
-impl GMapsClient<Validated> {
-    
-    /// Queries the places api obtaining the details of a single place given as text
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Sends a GET request to an api path, appending the api key and the default
+    /// language and region of the client to the parameters
     /// 
     /// parameters:
-    ///     * place: Description of the desired place in natural language
-    /// returns: serde_json::Value 
-    ///
-    pub async fn find_single_place_from_text(&self, place: &str) -> serde_json::Value {    
-        
-        let url = format!("{}/maps/api/place/findplacefromtext/json?input={}&inputtype=textquery&fields=name,place_id,geometry,formatted_address&locationbias=point:50,10&key={}",
-            self.base_url, place, self.api_key);
-        
-        let response = 
-            reqwest::get(url)
-            .await
-            .unwrap()
-            .json::<serde_json::Value>()
+    ///     * api: Api the path belongs to, selecting the base url
+    ///     * path: Path of the api, such as maps/api/directions/json
+    ///     * params: Query parameters of the request, the key excluded
+    /// returns: Result<R, GMapsClientError>
+    async fn get_api<R: DeserializeOwned + ApiResponse>(
+        &self,
+        api: Api,
+        path: &str,
+        params: Vec<(&'static str, String)>,
+    ) -> Result<R, GMapsClientError> {
+
+        let params = self.with_defaults(params);
+
+        self.get_checked(api, path, params).await
+    }
+
+    /// Adds the default language and region of the client, unless given
+    fn with_defaults(&self, params: Vec<(&'static str, String)>) -> QueryParams {
+
+        let mut params = QueryParams::from(params);
+
+        if let Some(language) = &self.language {
+            params.push_default("language", language);
+        }
+
+        if let Some(region) = &self.region {
+            params.push_default("region", region);
+        }
+
+        params
+    }
+
+    /// Sends a GET request to an api path until its response carries the OK status
+    /// or the retry policy gives up, every attempt waiting for the rate limiter of the api
+    /// and failing over to the next key when denied. Successful responses of the cached
+    /// apis are served from and stored in the cache
+    /// 
+    /// returns: Result<R, GMapsClientError>
+    async fn get_checked<R: DeserializeOwned + ApiResponse>(
+        &self,
+        api: Api,
+        path: &str,
+        params: QueryParams,
+    ) -> Result<R, GMapsClientError> {
+
+        // the credentials are left out of the cache keys anyway
+        let url = params.to_url(&format!("{}/{}", self.base_url(api), path));
+        let cache_key = self.cache_key(api, url.as_str());
+
+        if let Some(key) = &cache_key {
+            if let Some(body) = self.cache_get(key).await {
+                if let Ok(response) = decode_json::<R>(&body) {
+                    return Ok(response);
+                }
+            }
+        }
+
+        let (response, body): (R, String) = self.get_uncached(api, path, &params).await?;
+
+        if let Some(key) = cache_key.filter(|_| response.is_cacheable()) {
+            self.cache_insert(&key, url.as_str(), &body).await;
+        }
+        Ok(response)
+    }
+
+    /// Sends a GET request to an api path like get_api, bypassing the cache, for
+    /// responses that are only valid once such as the pages of a search
+    /// 
+    /// returns: Result<R, GMapsClientError>
+    async fn get_api_uncached<R: DeserializeOwned + ApiResponse>(
+        &self,
+        api: Api,
+        path: &str,
+        params: Vec<(&'static str, String)>,
+    ) -> Result<R, GMapsClientError> {
+
+        let params = self.with_defaults(params);
+
+        let (response, _body) = self.get_uncached(api, path, &params).await?;
+        Ok(response)
+    }
+
+    /// Sends a GET request to an api path with retries and key failover, without
+    /// looking at the cache
+    /// 
+    /// returns: Result<(R, String), GMapsClientError>
+    async fn get_uncached<R: DeserializeOwned + ApiResponse>(
+        &self,
+        api: Api,
+        path: &str,
+        params: &QueryParams,
+    ) -> Result<(R, String), GMapsClientError> {
+
+        self.with_retry(|| self.with_keys(api, path, params, |url| self.get_response::<R>(api, url)))
             .await
-            .unwrap();
-    
-        response
     }
 
-    /// Queries the places api obtaining a list of places and their details given a natural language query
+    /// Sends a single GET request once the rate limiter of the api allows it, returning
+    /// the decoded response along with its body when it carries the OK status
     /// 
-    /// parameters:
-    ///     * query: Description of the desired place in natural language
-    /// returns: serde_json::Value 
-    pub async fn find_places_from_text(&self, query: &str) -> serde_json::Value {
-
-        let url = format!(
-            "{}/maps/api/place/textsearch/json?query={}&radius={}&key={}",
-            self.base_url,
-            query,
-            5000,
-            self.api_key,
-            );
-        
-        let response = reqwest::get(url)
+    /// returns: Result<(R, String), GMapsClientError>
+    async fn get_response<R: DeserializeOwned + ApiResponse>(
+        &self,
+        api: Api,
+        url: Url,
+    ) -> Result<(R, String), GMapsClientError> {
+
+        self.acquire(api).await?;
+        let body = self.get_body(url.as_str()).await?;
+        let response: R = decode_json(&body)?;
+        response.check_status()?;
+
+        Ok((response, body))
+    }
+
+    /// Sends a GET request to the given url and returns the body
+    /// 
+    /// returns: Result<String, GMapsClientError>
+    async fn get_body(&self, url: &str) -> Result<String, GMapsClientError> {
+
+        self.request(self.http.get(url))
+            .send()
             .await
-            .unwrap()
-            .json::<serde_json::Value>()
+            .and_then(|response| response.error_for_status())
+            .map_err(GMapsClientError::request_failure)?
+            .text()
             .await
-            .unwrap();
-        
-        response
+            .map_err(GMapsClientError::request_failure)
+    }
+}
+
+/// Responses carrying the status of the request in their body
+pub(crate) trait ApiResponse {
+
+    /// Maps every status other than OK into the matching GMapsClientError
+    fn check_status(&self) -> Result<(), GMapsClientError>;
+
+    /// Whether the response may be stored in the cache
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+}
+
+/// Decodes a json body, keeping the beginning of the body in the error on failure
+/// 
+/// returns: Result<R, GMapsClientError>
+pub(crate) fn decode_json<R: DeserializeOwned>(body: &str) -> Result<R, GMapsClientError> {
+    serde_json::from_str(body).map_err(|source| GMapsClientError::Decode {
+        snippet: body.chars().take(DECODE_SNIPPET_LEN).collect(),
+        source,
+    })
+}
+
+/// Random number seeded by the standard library, good enough for jitter and
+/// session tokens without pulling in a dedicated crate
+pub(crate) fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+#[cfg(test)]
+impl GMapsClient<Invalidated> {
+
+    /// Skips the api key validation so that tests can run without network access
+    pub(crate) fn assume_validated(self) -> GMapsClient<Validated> {
+        self.into_state()
     }
 }
 
+/// Builder of the test clients, for the tests that need options besides the base url
+#[cfg(test)]
+pub(crate) fn test_builder(base_url: Option<&str>) -> GMapsClientBuilder {
+    let builder = GMapsClient::builder().api_key("test_key");
+    match base_url {
+        Some(base_url) => builder.base_url(base_url),
+        None => builder,
+    }
+}
 
+/// Validated client with a test key, reaching the given base url when one is given
+#[cfg(test)]
+pub(crate) fn test_client(base_url: Option<&str>) -> GMapsClient<Validated> {
+    test_builder(base_url).build().unwrap().assume_validated()
+}
 
 #[cfg(test)]
 pub mod tests {
@@ -182,9 +531,8 @@ pub mod tests {
         let gmaps = GMapsClient::new().unwrap();
         let gmaps = gmaps.validate_api_key().await.unwrap();
 
-        let response = gmaps.find_places_from_text("pizza party alba iulia").await;
-        let results = response["results"].clone();
-        assert_eq!(results[0]["name"], "Pizza Party");
+        let response = gmaps.find_places_from_text("pizza party alba iulia").await.unwrap();
+        assert_eq!(response.results[0].name.as_deref(), Some("Pizza Party"));
         
     }
 
@@ -0,0 +1,468 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::places::{PlaceDetailsRequest, PlaceStatus, SessionToken};
+use crate::types::LatLng;
+use crate::{Api, ApiResponse, GMapsClient, GMapsClientError, Validated};
+
+/// Maximum number of countries an autocomplete request can be restricted to
+const MAX_COUNTRIES: usize = 5;
+
+/// Part of a text matching the input, in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchedSubstring {
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Part of a prediction description, such as the city or the country
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Term {
+    pub offset: usize,
+    pub value: String,
+}
+
+/// Prediction description split into its main and secondary texts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructuredFormatting {
+    pub main_text: String,
+
+    #[serde(default)]
+    pub main_text_matched_substrings: Vec<MatchedSubstring>,
+
+    #[serde(default)]
+    pub secondary_text: Option<String>,
+
+    #[serde(default)]
+    pub secondary_text_matched_substrings: Vec<MatchedSubstring>,
+}
+
+/// A suggestion returned by the place and query autocomplete apis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Prediction {
+    pub description: String,
+
+    /// Missing for query predictions that are not a place
+    #[serde(default)]
+    pub place_id: Option<String>,
+
+    #[serde(default)]
+    pub types: Vec<String>,
+
+    #[serde(default)]
+    pub matched_substrings: Vec<MatchedSubstring>,
+
+    #[serde(default)]
+    pub structured_formatting: Option<StructuredFormatting>,
+
+    #[serde(default)]
+    pub terms: Vec<Term>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response of the place and query autocomplete requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutocompleteResponse {
+    #[serde(default)]
+    pub predictions: Vec<Prediction>,
+
+    pub status: PlaceStatus,
+
+    #[serde(default)]
+    pub error_message: Option<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ApiResponse for AutocompleteResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+}
+
+/// Builder for a place autocomplete request, obtained from GMapsClient::autocomplete
+/// or AutocompleteSession::autocomplete
+#[derive(Debug)]
+pub struct AutocompleteRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    input: String,
+    offset: Option<usize>,
+    location: Option<(LatLng, u32)>,
+    strict_bounds: bool,
+    countries: Vec<String>,
+    types: Vec<String>,
+    language: Option<String>,
+    session_token: Option<SessionToken>,
+}
+
+impl<'a> AutocompleteRequest<'a> {
+
+    /// Position of the cursor in the input, the text after it being ignored
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Prefers the places within radius meters of the location
+    pub fn location(mut self, location: LatLng, radius: u32) -> Self {
+        self.location = Some((location, radius));
+        self
+    }
+
+    /// Keeps only the places within the area given to location
+    pub fn strict_bounds(mut self, strict_bounds: bool) -> Self {
+        self.strict_bounds = strict_bounds;
+        self
+    }
+
+    /// Restricts the predictions to a country, as an ISO 3166-1 alpha-2 code such as "ro".
+    /// Can be called for up to 5 countries, the following ones being ignored
+    pub fn country(mut self, country: &str) -> Self {
+        if self.countries.len() < MAX_COUNTRIES {
+            self.countries.push(country.to_string());
+        }
+        self
+    }
+
+    /// Restricts the predictions to a type or a type collection such as "(cities)",
+    /// can be called multiple times
+    pub fn place_type(mut self, place_type: &str) -> Self {
+        self.types.push(place_type.to_string());
+        self
+    }
+
+    /// Overrides the default language of the client
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Groups the request in the session identified by the token, see AutocompleteSession
+    pub fn session_token(mut self, session_token: SessionToken) -> Self {
+        self.session_token = Some(session_token);
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("input", self.input.clone())];
+
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+
+        if let Some((location, radius)) = self.location {
+            params.push(("location", location.to_string()));
+            params.push(("radius", radius.to_string()));
+        }
+
+        if self.strict_bounds {
+            params.push(("strictbounds", "true".to_string()));
+        }
+
+        if !self.countries.is_empty() {
+            let countries: Vec<String> = self.countries.iter().map(|c| format!("country:{}", c)).collect();
+            params.push(("components", countries.join("|")));
+        }
+
+        if !self.types.is_empty() {
+            params.push(("types", self.types.join("|")));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+
+        if let Some(session_token) = &self.session_token {
+            params.push(("sessiontoken", session_token.to_string()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the place autocomplete api
+    /// 
+    /// returns: Result<AutocompleteResponse, GMapsClientError>
+    pub async fn send(self) -> Result<AutocompleteResponse, GMapsClientError> {
+        self.client
+            .get_api(Api::Places, "maps/api/place/autocomplete/json", self.params())
+            .await
+    }
+}
+
+/// Builder for a query autocomplete request, obtained from GMapsClient::query_autocomplete
+#[derive(Debug)]
+pub struct QueryAutocompleteRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    input: String,
+    offset: Option<usize>,
+    location: Option<(LatLng, u32)>,
+    language: Option<String>,
+}
+
+impl<'a> QueryAutocompleteRequest<'a> {
+
+    /// Position of the cursor in the input, the text after it being ignored
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Prefers the predictions within radius meters of the location
+    pub fn location(mut self, location: LatLng, radius: u32) -> Self {
+        self.location = Some((location, radius));
+        self
+    }
+
+    /// Overrides the default language of the client
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("input", self.input.clone())];
+
+        if let Some(offset) = self.offset {
+            params.push(("offset", offset.to_string()));
+        }
+
+        if let Some((location, radius)) = self.location {
+            params.push(("location", location.to_string()));
+            params.push(("radius", radius.to_string()));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the query autocomplete api
+    /// 
+    /// returns: Result<AutocompleteResponse, GMapsClientError>
+    pub async fn send(self) -> Result<AutocompleteResponse, GMapsClientError> {
+        self.client
+            .get_api(Api::Places, "maps/api/place/queryautocomplete/json", self.params())
+            .await
+    }
+}
+
+/// A typing session: every autocomplete request of the session and the place
+/// details request ending it share the same SessionToken, so that google bills
+/// them as a single session
+#[derive(Debug, Clone)]
+pub struct AutocompleteSession<'a> {
+    client: &'a GMapsClient<Validated>,
+    session_token: SessionToken,
+}
+
+impl<'a> AutocompleteSession<'a> {
+
+    pub fn session_token(&self) -> &SessionToken {
+        &self.session_token
+    }
+
+    /// Starts an autocomplete request belonging to the session
+    pub fn autocomplete(&self, input: &str) -> AutocompleteRequest<'a> {
+        self.client
+            .autocomplete(input)
+            .session_token(self.session_token.clone())
+    }
+
+    /// Starts the place details request of the selected prediction, ending the session
+    pub fn place_details(self, place_id: &str) -> PlaceDetailsRequest<'a> {
+        self.client
+            .place_details(place_id)
+            .session_token(self.session_token)
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a request predicting the places matching a partially typed input.
+    /// See autocomplete_session to group the requests of a typing session
+    /// 
+    /// parameters:
+    ///     * input: Text typed so far
+    /// returns: AutocompleteRequest, sent with AutocompleteRequest::send
+    pub fn autocomplete(&self, input: &str) -> AutocompleteRequest<'_> {
+        AutocompleteRequest {
+            client: self,
+            input: input.to_string(),
+            offset: None,
+            location: None,
+            strict_bounds: false,
+            countries: Vec::new(),
+            types: Vec::new(),
+            language: None,
+            session_token: None,
+        }
+    }
+
+    /// Starts a request predicting category style queries, such as "chargers near Cluj",
+    /// along with places matching a partially typed input
+    /// 
+    /// parameters:
+    ///     * input: Text typed so far
+    /// returns: QueryAutocompleteRequest, sent with QueryAutocompleteRequest::send
+    pub fn query_autocomplete(&self, input: &str) -> QueryAutocompleteRequest<'_> {
+        QueryAutocompleteRequest {
+            client: self,
+            input: input.to_string(),
+            offset: None,
+            location: None,
+            language: None,
+        }
+    }
+
+    /// Starts a typing session with a newly generated SessionToken
+    /// 
+    /// returns: AutocompleteSession
+    pub fn autocomplete_session(&self) -> AutocompleteSession<'_> {
+        AutocompleteSession {
+            client: self,
+            session_token: SessionToken::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    pub fn test_autocomplete_params() {
+        let client = test_client(None);
+        let request = client
+            .autocomplete("Piata Unirii")
+            .offset(5)
+            .location(LatLng::new(46.77, 23.59), 10000)
+            .strict_bounds(true)
+            .country("ro")
+            .country("md")
+            .place_type("geocode");
+
+        assert_eq!(
+            request.params(),
+            vec![
+                ("input", "Piata Unirii".to_string()),
+                ("offset", "5".to_string()),
+                ("location", "46.77,23.59".to_string()),
+                ("radius", "10000".to_string()),
+                ("strictbounds", "true".to_string()),
+                ("components", "country:ro|country:md".to_string()),
+                ("types", "geocode".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_query_autocomplete_params() {
+        let client = test_client(None);
+        let request = client
+            .query_autocomplete("chargers near Cl")
+            .offset(14)
+            .location(LatLng::new(46.77, 23.59), 20000)
+            .language("ro");
+
+        assert_eq!(
+            request.params(),
+            vec![
+                ("input", "chargers near Cl".to_string()),
+                ("offset", "14".to_string()),
+                ("location", "46.77,23.59".to_string()),
+                ("radius", "20000".to_string()),
+                ("language", "ro".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_deserialize_query_prediction() {
+        let body = r#"{
+            "predictions": [{
+                "description": "chargers near Cluj-Napoca",
+                "matched_substrings": [{ "length": 8, "offset": 0 }],
+                "structured_formatting": {
+                    "main_text": "chargers",
+                    "main_text_matched_substrings": [{ "length": 8, "offset": 0 }],
+                    "secondary_text": "near Cluj-Napoca"
+                },
+                "terms": [
+                    { "offset": 0, "value": "chargers" },
+                    { "offset": 9, "value": "near" },
+                    { "offset": 14, "value": "Cluj-Napoca" }
+                ]
+            }],
+            "status": "OK"
+        }"#;
+
+        let response: AutocompleteResponse = serde_json::from_str(body).unwrap();
+        let prediction = &response.predictions[0];
+        assert_eq!(prediction.place_id, None);
+        assert_eq!(prediction.matched_substrings[0], MatchedSubstring { offset: 0, length: 8 });
+        assert_eq!(prediction.terms[2].value, "Cluj-Napoca");
+    }
+
+    #[tokio::test]
+    pub async fn test_session_token_is_shared_with_place_details() {
+        let server = MockServer::start().await;
+        let client = test_client(Some(&server.uri()));
+
+        let session = client.autocomplete_session();
+        let token = session.session_token().to_string();
+
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/autocomplete/json"))
+            .and(query_param("sessiontoken", token.as_str()))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{
+                    "predictions": [{
+                        "description": "Piata Unirii, Cluj-Napoca, Romania",
+                        "place_id": "ChIJ123",
+                        "matched_substrings": [{ "length": 5, "offset": 0 }],
+                        "structured_formatting": {
+                            "main_text": "Piata Unirii",
+                            "main_text_matched_substrings": [{ "length": 5, "offset": 0 }],
+                            "secondary_text": "Cluj-Napoca, Romania"
+                        },
+                        "terms": [{ "offset": 0, "value": "Piata Unirii" }],
+                        "types": ["route"]
+                    }],
+                    "status": "OK"
+                }"#,
+            ))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/details/json"))
+            .and(query_param("sessiontoken", token.as_str()))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "result": { "name": "Piata Unirii" }, "status": "OK" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        session.autocomplete("Piata").send().await.unwrap();
+        let response = session.autocomplete("Piata U").send().await.unwrap();
+
+        let prediction = &response.predictions[0];
+        assert_eq!(prediction.structured_formatting.as_ref().unwrap().main_text, "Piata Unirii");
+
+        let place_id = prediction.place_id.clone().unwrap();
+        assert!(session.place_details(&place_id).send().await.is_ok());
+    }
+}
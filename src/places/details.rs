@@ -0,0 +1,453 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use std::fmt;
+
+use crate::geocoding::AddressComponent;
+use crate::places::{Photo, PlaceStatus};
+use crate::types::Geometry;
+use crate::{random_u64, Api, ApiResponse, GMapsClient, GMapsClientError, Validated};
+
+/// Billing category of a place field, each category being charged as a separate SKU
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaceSku {
+    Basic,
+    Contact,
+    Atmosphere,
+}
+
+/// Fields that can be requested from the place details api
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaceField {
+    AddressComponents,
+    BusinessStatus,
+    FormattedAddress,
+    Geometry,
+    Name,
+    Photos,
+    PlaceId,
+    PlusCode,
+    Types,
+    Url,
+    UtcOffset,
+    Vicinity,
+    WheelchairAccessibleEntrance,
+    CurrentOpeningHours,
+    FormattedPhoneNumber,
+    InternationalPhoneNumber,
+    OpeningHours,
+    Website,
+    PriceLevel,
+    Rating,
+    Reviews,
+    UserRatingsTotal,
+}
+
+impl PlaceField {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlaceField::AddressComponents => "address_components",
+            PlaceField::BusinessStatus => "business_status",
+            PlaceField::FormattedAddress => "formatted_address",
+            PlaceField::Geometry => "geometry",
+            PlaceField::Name => "name",
+            PlaceField::Photos => "photos",
+            PlaceField::PlaceId => "place_id",
+            PlaceField::PlusCode => "plus_code",
+            PlaceField::Types => "types",
+            PlaceField::Url => "url",
+            PlaceField::UtcOffset => "utc_offset",
+            PlaceField::Vicinity => "vicinity",
+            PlaceField::WheelchairAccessibleEntrance => "wheelchair_accessible_entrance",
+            PlaceField::CurrentOpeningHours => "current_opening_hours",
+            PlaceField::FormattedPhoneNumber => "formatted_phone_number",
+            PlaceField::InternationalPhoneNumber => "international_phone_number",
+            PlaceField::OpeningHours => "opening_hours",
+            PlaceField::Website => "website",
+            PlaceField::PriceLevel => "price_level",
+            PlaceField::Rating => "rating",
+            PlaceField::Reviews => "reviews",
+            PlaceField::UserRatingsTotal => "user_ratings_total",
+        }
+    }
+
+    /// Billing category the field belongs to
+    pub fn sku(&self) -> PlaceSku {
+        match self {
+            PlaceField::CurrentOpeningHours
+            | PlaceField::FormattedPhoneNumber
+            | PlaceField::InternationalPhoneNumber
+            | PlaceField::OpeningHours
+            | PlaceField::Website => PlaceSku::Contact,
+            PlaceField::PriceLevel
+            | PlaceField::Rating
+            | PlaceField::Reviews
+            | PlaceField::UserRatingsTotal => PlaceSku::Atmosphere,
+            _ => PlaceSku::Basic,
+        }
+    }
+}
+
+/// Token grouping the autocomplete requests of a typing session with the place
+/// details request ending it, so that google bills them as a single session
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionToken(String);
+
+impl SessionToken {
+
+    /// Generates a new random token, formatted as a version 4 uuid
+    pub fn new() -> SessionToken {
+        let high = (random_u64() & !0xf000) | 0x4000;
+        let low = (random_u64() & !(0xc000 << 48)) | (0x8000 << 48);
+
+        SessionToken(format!(
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            high >> 32,
+            (high >> 16) & 0xffff,
+            high & 0xffff,
+            low >> 48,
+            low & 0xffff_ffff_ffff,
+        ))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for SessionToken {
+    fn default() -> SessionToken {
+        SessionToken::new()
+    }
+}
+
+impl fmt::Display for SessionToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Time of the week a place opens or closes at
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpeningTime {
+    /// Day of the week, 0 being sunday
+    pub day: u8,
+
+    /// Time of the day in the hhmm format
+    pub time: String,
+}
+
+/// Interval during which a place is open, close is missing for places open around the clock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpeningPeriod {
+    pub open: OpeningTime,
+
+    #[serde(default)]
+    pub close: Option<OpeningTime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OpeningHours {
+    #[serde(default)]
+    pub open_now: Option<bool>,
+
+    #[serde(default)]
+    pub periods: Vec<OpeningPeriod>,
+
+    #[serde(default)]
+    pub weekday_text: Vec<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Review {
+    pub author_name: String,
+
+    #[serde(default)]
+    pub author_url: Option<String>,
+
+    #[serde(default)]
+    pub language: Option<String>,
+
+    pub rating: u8,
+
+    #[serde(default)]
+    pub relative_time_description: String,
+
+    #[serde(default)]
+    pub text: String,
+
+    /// Time of the review, in seconds since the unix epoch
+    pub time: u64,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Details of a place, only the requested fields being set
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaceDetails {
+    #[serde(default)]
+    pub place_id: Option<String>,
+
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub formatted_address: Option<String>,
+
+    #[serde(default)]
+    pub address_components: Vec<AddressComponent>,
+
+    #[serde(default)]
+    pub geometry: Option<Geometry>,
+
+    #[serde(default)]
+    pub types: Vec<String>,
+
+    #[serde(default)]
+    pub business_status: Option<String>,
+
+    #[serde(default)]
+    pub formatted_phone_number: Option<String>,
+
+    #[serde(default)]
+    pub international_phone_number: Option<String>,
+
+    #[serde(default)]
+    pub website: Option<String>,
+
+    /// Url of the google maps page of the place
+    #[serde(default)]
+    pub url: Option<String>,
+
+    #[serde(default)]
+    pub opening_hours: Option<OpeningHours>,
+
+    #[serde(default)]
+    pub current_opening_hours: Option<OpeningHours>,
+
+    #[serde(default)]
+    pub photos: Vec<Photo>,
+
+    #[serde(default)]
+    pub reviews: Vec<Review>,
+
+    #[serde(default)]
+    pub rating: Option<f64>,
+
+    #[serde(default)]
+    pub user_ratings_total: Option<u32>,
+
+    #[serde(default)]
+    pub price_level: Option<u8>,
+
+    /// Offset from utc of the timezone of the place, in minutes
+    #[serde(default)]
+    pub utc_offset: Option<i32>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response of the place details request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaceDetailsResponse {
+    #[serde(default)]
+    pub result: Option<PlaceDetails>,
+
+    pub status: PlaceStatus,
+
+    #[serde(default)]
+    pub error_message: Option<String>,
+
+    #[serde(default)]
+    pub html_attributions: Vec<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ApiResponse for PlaceDetailsResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+}
+
+/// Builder for a place details request, obtained from GMapsClient::place_details
+#[derive(Debug)]
+pub struct PlaceDetailsRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    place_id: String,
+    fields: Vec<PlaceField>,
+    language: Option<String>,
+    region: Option<String>,
+    session_token: Option<SessionToken>,
+}
+
+impl<'a> PlaceDetailsRequest<'a> {
+
+    /// Restricts the response to the given fields, replacing the fields of an earlier
+    /// call. Without fields every field is returned and billed
+    pub fn fields(mut self, fields: &[PlaceField]) -> Self {
+        self.fields.clear();
+        for field in fields {
+            if !self.fields.contains(field) {
+                self.fields.push(*field);
+            }
+        }
+        self
+    }
+
+    /// Overrides the default language of the client
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    /// Overrides the default region of the client
+    pub fn region(mut self, region: &str) -> Self {
+        self.region = Some(region.to_string());
+        self
+    }
+
+    /// Ends the autocomplete session identified by the token
+    pub fn session_token(mut self, session_token: SessionToken) -> Self {
+        self.session_token = Some(session_token);
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("place_id", self.place_id.clone())];
+
+        if !self.fields.is_empty() {
+            let fields: Vec<&str> = self.fields.iter().map(|f| f.as_str()).collect();
+            params.push(("fields", fields.join(",")));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+
+        if let Some(region) = &self.region {
+            params.push(("region", region.clone()));
+        }
+
+        if let Some(session_token) = &self.session_token {
+            params.push(("sessiontoken", session_token.to_string()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the place details api
+    /// 
+    /// returns: Result<PlaceDetailsResponse, GMapsClientError>
+    pub async fn send(self) -> Result<PlaceDetailsResponse, GMapsClientError> {
+        self.client
+            .get_api(Api::Places, "maps/api/place/details/json", self.params())
+            .await
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a request obtaining the details of a place
+    /// 
+    /// parameters:
+    ///     * place_id: Id of the place, as found in search results
+    /// returns: PlaceDetailsRequest, sent with PlaceDetailsRequest::send
+    pub fn place_details(&self, place_id: &str) -> PlaceDetailsRequest<'_> {
+        PlaceDetailsRequest {
+            client: self,
+            place_id: place_id.to_string(),
+            fields: Vec::new(),
+            language: None,
+            region: None,
+            session_token: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+
+    #[test]
+    pub fn test_place_details_params() {
+        let client = test_client(None);
+        let session_token = SessionToken::new();
+        let request = client
+            .place_details("ChIJ123")
+            .fields(&[PlaceField::Photos])
+            .fields(&[PlaceField::Name, PlaceField::Website, PlaceField::Reviews, PlaceField::Name])
+            .language("ro")
+            .session_token(session_token.clone());
+
+        assert_eq!(
+            request.params(),
+            vec![
+                ("place_id", "ChIJ123".to_string()),
+                ("fields", "name,website,reviews".to_string()),
+                ("language", "ro".to_string()),
+                ("sessiontoken", session_token.to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_field_skus() {
+        assert_eq!(PlaceField::Name.sku(), PlaceSku::Basic);
+        assert_eq!(PlaceField::Website.sku(), PlaceSku::Contact);
+        assert_eq!(PlaceField::Reviews.sku(), PlaceSku::Atmosphere);
+    }
+
+    #[test]
+    pub fn test_session_token_format() {
+        let token = SessionToken::new();
+        let parts: Vec<&str> = token.as_str().split('-').collect();
+
+        assert_eq!(parts.iter().map(|p| p.len()).collect::<Vec<_>>(), vec![8, 4, 4, 4, 12]);
+        assert!(parts[2].starts_with('4'));
+        assert_ne!(token, SessionToken::new());
+    }
+
+    #[test]
+    pub fn test_deserialize_place_details_response() {
+        let body = r#"{
+            "html_attributions": [],
+            "result": {
+                "name": "Kaufland Alba Iulia EV Charger",
+                "formatted_phone_number": "0258 123 456",
+                "website": "https://example.com",
+                "opening_hours": {
+                    "open_now": true,
+                    "periods": [{ "open": { "day": 0, "time": "0000" } }],
+                    "weekday_text": ["Monday: Open 24 hours"]
+                },
+                "reviews": [{
+                    "author_name": "Ion",
+                    "rating": 5,
+                    "relative_time_description": "a week ago",
+                    "text": "Fast charging",
+                    "time": 1700000000
+                }]
+            },
+            "status": "OK"
+        }"#;
+
+        let response: PlaceDetailsResponse = serde_json::from_str(body).unwrap();
+        let details = response.result.unwrap();
+        assert_eq!(details.formatted_phone_number.as_deref(), Some("0258 123 456"));
+        assert_eq!(details.opening_hours.unwrap().periods[0].close, None);
+        assert_eq!(details.reviews[0].rating, 5);
+    }
+}
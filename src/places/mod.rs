@@ -0,0 +1,263 @@
+use futures::stream::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::types::Geometry;
+use crate::{ApiResponse, GMapsClient, GMapsClientError, Validated};
+
+mod autocomplete;
+mod details;
+mod nearby;
+mod pagination;
+mod photos;
+mod search;
+
+pub use autocomplete::{
+    AutocompleteRequest, AutocompleteResponse, AutocompleteSession, MatchedSubstring, Prediction,
+    QueryAutocompleteRequest, StructuredFormatting, Term,
+};
+pub use details::{
+    OpeningHours, OpeningPeriod, OpeningTime, PlaceDetails, PlaceDetailsRequest,
+    PlaceDetailsResponse, PlaceField, PlaceSku, Review, SessionToken,
+};
+pub use nearby::{NearbyRanking, NearbySearchRequest, NearbySearchResponse};
+pub use photos::{Photo, PlacePhoto};
+pub use search::{FindPlaceRequest, LocationBias, TextSearchRequest};
+
+/// Status codes returned by the places api
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PlaceStatus {
+    Ok,
+    ZeroResults,
+    InvalidRequest,
+    OverQueryLimit,
+    RequestDenied,
+    NotFound,
+    UnknownError,
+}
+
+impl PlaceStatus {
+
+    /// Maps every status other than OK into the matching GMapsClientError
+    pub(crate) fn check(self, error_message: &Option<String>) -> Result<(), GMapsClientError> {
+        match self {
+            PlaceStatus::Ok => Ok(()),
+            PlaceStatus::ZeroResults => Err(GMapsClientError::ZeroResults),
+            PlaceStatus::InvalidRequest => Err(GMapsClientError::InvalidRequest),
+            PlaceStatus::OverQueryLimit => Err(GMapsClientError::OverQueryLimit),
+            PlaceStatus::RequestDenied => Err(GMapsClientError::RequestDenied {
+                error_message: error_message.clone(),
+            }),
+            PlaceStatus::NotFound => Err(GMapsClientError::NotFound),
+            PlaceStatus::UnknownError => Err(GMapsClientError::UnknownError),
+        }
+    }
+}
+
+/// A place as returned by the find place, text search and nearby search requests.
+/// Every field is optional since the api only returns the requested ones
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Place {
+    #[serde(default)]
+    pub place_id: Option<String>,
+
+    #[serde(default)]
+    pub name: Option<String>,
+
+    #[serde(default)]
+    pub formatted_address: Option<String>,
+
+    /// Simplified address, returned by the nearby search instead of formatted_address
+    #[serde(default)]
+    pub vicinity: Option<String>,
+
+    #[serde(default)]
+    pub geometry: Option<Geometry>,
+
+    #[serde(default)]
+    pub types: Vec<String>,
+
+    #[serde(default)]
+    pub business_status: Option<String>,
+
+    #[serde(default)]
+    pub opening_hours: Option<OpeningHours>,
+
+    #[serde(default)]
+    pub photos: Vec<Photo>,
+
+    #[serde(default)]
+    pub rating: Option<f64>,
+
+    #[serde(default)]
+    pub user_ratings_total: Option<u32>,
+
+    #[serde(default)]
+    pub price_level: Option<u8>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response of the find place from text request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FindPlaceResponse {
+    #[serde(default)]
+    pub candidates: Vec<Place>,
+
+    pub status: PlaceStatus,
+
+    #[serde(default)]
+    pub error_message: Option<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Response of the text search request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextSearchResponse {
+    #[serde(default)]
+    pub results: Vec<Place>,
+
+    pub status: PlaceStatus,
+
+    #[serde(default)]
+    pub error_message: Option<String>,
+
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+
+    #[serde(default)]
+    pub html_attributions: Vec<String>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl ApiResponse for FindPlaceResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+}
+
+impl ApiResponse for TextSearchResponse {
+    fn check_status(&self) -> Result<(), GMapsClientError> {
+        self.status.check(&self.error_message)
+    }
+
+    /// Google expires the next_page_token within minutes, long before a cached
+    /// response would, so only the last page of a search is cached
+    fn is_cacheable(&self) -> bool {
+        self.next_page_token.is_none()
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Queries the places api obtaining the details of a single place given as text,
+    /// without any location bias. See find_place for more options
+    /// 
+    /// parameters:
+    ///     * place: Description of the desired place in natural language
+    /// returns: Result<FindPlaceResponse, GMapsClientError>
+    ///
+    pub async fn find_single_place_from_text(&self, place: &str) -> Result<FindPlaceResponse, GMapsClientError> {
+        self.find_place(place).send().await
+    }
+
+    /// Queries the places api obtaining a list of places and their details given a natural language query.
+    /// See text_search for more options
+    /// 
+    /// parameters:
+    ///     * query: Description of the desired place in natural language
+    /// returns: Result<TextSearchResponse, GMapsClientError>
+    pub async fn find_places_from_text(&self, query: &str) -> Result<TextSearchResponse, GMapsClientError> {
+        self.text_search(query).send().await
+    }
+
+    /// Streams the places matching a natural language query, following the result
+    /// pages until max_results places were yielded or the results are exhausted.
+    /// Fetching a page waits for its token to become valid, a few seconds
+    /// 
+    /// parameters:
+    ///     * query: Description of the desired place in natural language
+    ///     * max_results: Maximum number of places yielded, google returns at most 60
+    /// returns: Stream<Item = Result<Place, GMapsClientError>>
+    pub fn find_places_stream<'a>(
+        &'a self,
+        query: &str,
+        max_results: usize,
+    ) -> impl Stream<Item = Result<Place, GMapsClientError>> + 'a {
+        self.text_search(query).stream(max_results)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::types::LatLng;
+
+    #[test]
+    pub fn test_deserialize_text_search_response() {
+        let body = r##"{
+            "html_attributions": [],
+            "results": [{
+                "name": "Pizza Party",
+                "place_id": "ChIJN1t_tDeuEmsRUsoyG83frY4",
+                "formatted_address": "Alba Iulia, Romania",
+                "geometry": {
+                    "location": { "lat": 46.07, "lng": 23.58 },
+                    "viewport": {
+                        "northeast": { "lat": 46.08, "lng": 23.59 },
+                        "southwest": { "lat": 46.06, "lng": 23.57 }
+                    }
+                },
+                "types": ["restaurant", "food"],
+                "icon_background_color": "#FF9E67"
+            }],
+            "status": "OK",
+            "new_top_level_field": 1
+        }"##;
+
+        let response: TextSearchResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.status, PlaceStatus::Ok);
+
+        let place = &response.results[0];
+        assert_eq!(place.name.as_deref(), Some("Pizza Party"));
+        assert_eq!(place.geometry.as_ref().unwrap().location, LatLng::new(46.07, 23.58));
+        assert_eq!(place.extra["icon_background_color"], "#FF9E67");
+        assert_eq!(response.extra["new_top_level_field"], 1);
+    }
+
+    #[test]
+    pub fn test_deserialize_find_place_zero_results() {
+        let body = r#"{ "candidates": [], "status": "ZERO_RESULTS" }"#;
+
+        let response: FindPlaceResponse = serde_json::from_str(body).unwrap();
+        assert_eq!(response.status, PlaceStatus::ZeroResults);
+        assert!(response.candidates.is_empty());
+        assert!(matches!(
+            response.status.check(&response.error_message),
+            Err(GMapsClientError::ZeroResults)
+        ));
+    }
+
+    #[test]
+    pub fn test_request_denied_keeps_error_message() {
+        let body = r#"{ "candidates": [], "status": "REQUEST_DENIED", "error_message": "The provided API key is invalid." }"#;
+
+        let response: FindPlaceResponse = serde_json::from_str(body).unwrap();
+        match response.status.check(&response.error_message) {
+            Err(GMapsClientError::RequestDenied { error_message }) => {
+                assert_eq!(error_message.as_deref(), Some("The provided API key is invalid."))
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
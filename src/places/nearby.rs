@@ -0,0 +1,206 @@
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::places::{pagination, Place, TextSearchResponse};
+use crate::types::LatLng;
+use crate::{Api, GMapsClient, GMapsClientError, Validated};
+
+/// Nearby search results share the model of the text search results
+pub type NearbySearchResponse = TextSearchResponse;
+
+/// How the nearby search results are selected and ordered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NearbyRanking {
+    /// Results within the given radius in meters, ordered by prominence
+    Radius(u32),
+    /// Results ordered by distance, requiring a type or a keyword
+    Distance,
+}
+
+/// Builder for a nearby search request, obtained from GMapsClient::nearby_search
+#[derive(Debug)]
+pub struct NearbySearchRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    location: LatLng,
+    ranking: NearbyRanking,
+    place_type: Option<String>,
+    keyword: Option<String>,
+    open_now: bool,
+    min_price: Option<u8>,
+    max_price: Option<u8>,
+    language: Option<String>,
+}
+
+impl<'a> NearbySearchRequest<'a> {
+
+    /// Restricts the results to places of the given type, such as "electric_vehicle_charging_station"
+    pub fn place_type(mut self, place_type: &str) -> Self {
+        self.place_type = Some(place_type.to_string());
+        self
+    }
+
+    /// Matches the keyword against the name, type, address and reviews of the places
+    pub fn keyword(mut self, keyword: &str) -> Self {
+        self.keyword = Some(keyword.to_string());
+        self
+    }
+
+    /// Keeps only the places open at the time of the request
+    pub fn open_now(mut self, open_now: bool) -> Self {
+        self.open_now = open_now;
+        self
+    }
+
+    /// Lowest price level of the results, from 0 to 4
+    pub fn min_price(mut self, min_price: u8) -> Self {
+        self.min_price = Some(min_price.min(4));
+        self
+    }
+
+    /// Highest price level of the results, from 0 to 4
+    pub fn max_price(mut self, max_price: u8) -> Self {
+        self.max_price = Some(max_price.min(4));
+        self
+    }
+
+    /// Overrides the default language of the client
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("location", self.location.to_string())];
+
+        match self.ranking {
+            NearbyRanking::Radius(radius) => params.push(("radius", radius.to_string())),
+            NearbyRanking::Distance => params.push(("rankby", "distance".to_string())),
+        }
+
+        if let Some(place_type) = &self.place_type {
+            params.push(("type", place_type.clone()));
+        }
+
+        if let Some(keyword) = &self.keyword {
+            params.push(("keyword", keyword.clone()));
+        }
+
+        if self.open_now {
+            params.push(("opennow", "true".to_string()));
+        }
+
+        if let Some(min_price) = self.min_price {
+            params.push(("minprice", min_price.to_string()));
+        }
+
+        if let Some(max_price) = self.max_price {
+            params.push(("maxprice", max_price.to_string()));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+
+        params
+    }
+
+    /// The api rejects such requests anyway, failing early saves a billed request
+    fn is_valid(&self) -> bool {
+        self.ranking != NearbyRanking::Distance || self.place_type.is_some() || self.keyword.is_some()
+    }
+
+    /// Sends the request to the nearby search api
+    /// 
+    /// returns: Result<NearbySearchResponse, GMapsClientError>
+    pub async fn send(self) -> Result<NearbySearchResponse, GMapsClientError> {
+
+        if !self.is_valid() {
+            return Err(GMapsClientError::InvalidRequest);
+        }
+
+        self.client
+            .get_api(Api::Places, "maps/api/place/nearbysearch/json", self.params())
+            .await
+    }
+
+    /// Streams the places found by the request, following the result pages until
+    /// max_results places were yielded or the results are exhausted
+    /// 
+    /// returns: Stream<Item = Result<Place, GMapsClientError>>
+    pub fn stream(self, max_results: usize) -> impl Stream<Item = Result<Place, GMapsClientError>> + 'a {
+
+        if !self.is_valid() {
+            return stream::once(async { Err(GMapsClientError::InvalidRequest) }).left_stream();
+        }
+
+        pagination::paginate(self.client, "maps/api/place/nearbysearch/json", self.params(), max_results)
+            .right_stream()
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a request searching for places around a location
+    /// 
+    /// parameters:
+    ///     * location: Center of the search
+    ///     * ranking: Radius of the search, or ordering of the results by distance
+    /// returns: NearbySearchRequest, sent with NearbySearchRequest::send
+    pub fn nearby_search(&self, location: LatLng, ranking: NearbyRanking) -> NearbySearchRequest<'_> {
+        NearbySearchRequest {
+            client: self,
+            location,
+            ranking,
+            place_type: None,
+            keyword: None,
+            open_now: false,
+            min_price: None,
+            max_price: None,
+            language: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+
+    #[test]
+    pub fn test_nearby_search_params() {
+        let client = test_client(None);
+        let request = client
+            .nearby_search(LatLng::new(46.07, 23.58), NearbyRanking::Radius(5000))
+            .place_type("electric_vehicle_charging_station")
+            .keyword("tesla")
+            .open_now(true)
+            .min_price(1)
+            .max_price(9)
+            .language("ro");
+
+        assert_eq!(
+            request.params(),
+            vec![
+                ("location", "46.07,23.58".to_string()),
+                ("radius", "5000".to_string()),
+                ("type", "electric_vehicle_charging_station".to_string()),
+                ("keyword", "tesla".to_string()),
+                ("opennow", "true".to_string()),
+                ("minprice", "1".to_string()),
+                ("maxprice", "4".to_string()),
+                ("language", "ro".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_rank_by_distance_requires_type_or_keyword() {
+        let client = test_client(None);
+        let response = client
+            .nearby_search(LatLng::new(46.07, 23.58), NearbyRanking::Distance)
+            .send()
+            .await;
+
+        assert!(matches!(response, Err(GMapsClientError::InvalidRequest)));
+    }
+}
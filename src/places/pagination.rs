@@ -0,0 +1,202 @@
+use futures::stream::{self, Stream, StreamExt};
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::places::{Place, TextSearchResponse};
+use crate::{Api, GMapsClient, GMapsClientError, Validated};
+
+/// Time google needs before a next_page_token becomes valid
+const PAGE_TOKEN_DELAY: Duration = Duration::from_secs(2);
+
+/// Time waited before asking again for a page whose token was not valid yet
+const PAGE_TOKEN_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Number of times a page is requested while its token answers INVALID_REQUEST
+const PAGE_TOKEN_ATTEMPTS: u32 = 5;
+
+enum Page {
+    First(Vec<(&'static str, String)>),
+    Next(String),
+    Done,
+}
+
+struct Pagination<'a> {
+    client: &'a GMapsClient<Validated>,
+    path: &'static str,
+    page: Page,
+    places: VecDeque<Place>,
+}
+
+impl<'a> Pagination<'a> {
+
+    /// Pages are never served from the cache, a cached page would carry an expired
+    /// next_page_token and a page token can only be used once anyway
+    async fn fetch_first(&self, params: Vec<(&'static str, String)>) -> Result<TextSearchResponse, GMapsClientError> {
+        self.client.get_api_uncached(Api::Places, self.path, params).await
+    }
+
+    async fn fetch_next(&self, token: &str) -> Result<TextSearchResponse, GMapsClientError> {
+        tokio::time::sleep(PAGE_TOKEN_DELAY).await;
+
+        let mut attempt = 1;
+        loop {
+            let params = vec![("pagetoken", token.to_string())];
+            match self.client.get_api_uncached(Api::Places, self.path, params).await {
+                Err(GMapsClientError::InvalidRequest) if attempt < PAGE_TOKEN_ATTEMPTS => {
+                    tokio::time::sleep(PAGE_TOKEN_RETRY_DELAY).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns the next place, fetching the next page once the current one is consumed
+    async fn next(mut self) -> Option<(Result<Place, GMapsClientError>, Pagination<'a>)> {
+        loop {
+            if let Some(place) = self.places.pop_front() {
+                return Some((Ok(place), self));
+            }
+
+            let response = match std::mem::replace(&mut self.page, Page::Done) {
+                Page::First(params) => self.fetch_first(params).await,
+                Page::Next(token) => self.fetch_next(&token).await,
+                Page::Done => return None,
+            };
+
+            match response {
+                Ok(response) => {
+                    if let Some(token) = response.next_page_token {
+                        self.page = Page::Next(token);
+                    }
+                    self.places.extend(response.results);
+                }
+                Err(GMapsClientError::ZeroResults) => return None,
+                Err(error) => return Some((Err(error), self)),
+            }
+        }
+    }
+}
+
+/// Streams the places of a search, following the next_page_token of every page
+/// until max_results places were yielded or the results are exhausted
+pub(crate) fn paginate<'a>(
+    client: &'a GMapsClient<Validated>,
+    path: &'static str,
+    params: Vec<(&'static str, String)>,
+    max_results: usize,
+) -> impl Stream<Item = Result<Place, GMapsClientError>> + 'a {
+    let pagination = Pagination {
+        client,
+        path,
+        page: Page::First(params),
+        places: VecDeque::new(),
+    };
+
+    stream::unfold(pagination, Pagination::next).take(max_results)
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::{test_builder, test_client, MemoryCache};
+    use futures::TryStreamExt;
+    use std::sync::Arc;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn page(names: &[&str], status: &str, next_page_token: Option<&str>) -> ResponseTemplate {
+        let results: Vec<_> = names.iter().map(|name| serde_json::json!({ "name": name })).collect();
+        let mut body = serde_json::json!({ "results": results, "status": status });
+        if let Some(token) = next_page_token {
+            body["next_page_token"] = serde_json::json!(token);
+        }
+        ResponseTemplate::new(200).set_body_json(body)
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_pagination_follows_tokens() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/textsearch/json"))
+            .and(query_param("query", "chargers"))
+            .respond_with(page(&["a", "b"], "OK", Some("second")))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("pagetoken", "second"))
+            .respond_with(page(&[], "INVALID_REQUEST", None))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("pagetoken", "second"))
+            .respond_with(page(&["c", "d"], "OK", Some("third")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(Some(&server.uri()));
+
+        let params = vec![("query", "chargers".to_string())];
+        let places: Vec<Place> = paginate(&client, "maps/api/place/textsearch/json", params, 3)
+            .try_collect()
+            .await
+            .unwrap();
+
+        let names: Vec<_> = places.iter().map(|p| p.name.clone().unwrap()).collect();
+        assert_eq!(names, vec!["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    pub async fn test_pagination_without_results() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(page(&[], "ZERO_RESULTS", None))
+            .mount(&server)
+            .await;
+
+        let client = test_client(Some(&server.uri()));
+
+        let places: Vec<Place> = paginate(&client, "maps/api/place/textsearch/json", vec![], 60)
+            .try_collect()
+            .await
+            .unwrap();
+        assert!(places.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_pagination_bypasses_the_cache() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/textsearch/json"))
+            .and(query_param("query", "chargers"))
+            .respond_with(page(&["a"], "OK", Some("second")))
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("pagetoken", "second"))
+            .respond_with(page(&["b"], "OK", None))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let cache = Arc::new(MemoryCache::new(100));
+        let client = test_builder(Some(&server.uri()))
+            .cache(cache.clone())
+            .build()
+            .unwrap()
+            .assume_validated();
+
+        for _ in 0..2 {
+            let places: Vec<Place> = client.find_places_stream("chargers", 60).try_collect().await.unwrap();
+            assert_eq!(places.len(), 2);
+        }
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+use reqwest::header::CONTENT_TYPE;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{Api, GMapsClient, GMapsClientError, Validated};
+
+/// Largest width or height the photo api serves
+const MAX_PHOTO_SIZE: u32 = 1600;
+
+/// Reference to a photo of a place, to be downloaded with GMapsClient::place_photo
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Photo {
+    pub photo_reference: String,
+
+    pub height: u32,
+
+    pub width: u32,
+
+    /// Attributions that must be displayed along with the photo
+    #[serde(default)]
+    pub html_attributions: Vec<String>,
+}
+
+/// A downloaded photo
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacePhoto {
+    pub content_type: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+impl GMapsClient<Validated> {
+
+    /// Sends the photo request, following the redirect to the image itself
+    async fn photo_response(
+        &self,
+        reference: &str,
+        max_width: Option<u32>,
+        max_height: Option<u32>,
+    ) -> Result<reqwest::Response, GMapsClientError> {
+
+        if max_width.is_none() && max_height.is_none() {
+            return Err(GMapsClientError::InvalidRequest);
+        }
+
+        let mut params = vec![("photo_reference", reference.to_string())];
+        if let Some(max_width) = max_width {
+            params.push(("maxwidth", max_width.clamp(1, MAX_PHOTO_SIZE).to_string()));
+        }
+        if let Some(max_height) = max_height {
+            params.push(("maxheight", max_height.clamp(1, MAX_PHOTO_SIZE).to_string()));
+        }
+        let params = params.into();
+
+        self.with_retry(|| {
+            self.with_keys(Api::Places, "maps/api/place/photo", &params, |url| async move {
+                self.acquire(Api::Places).await?;
+                self.request(self.http.get(url))
+                    .send()
+                    .await
+                    .and_then(|response| response.error_for_status())
+                    .map_err(GMapsClientError::request_failure)
+            })
+        })
+        .await
+    }
+
+    /// Downloads a photo of a place, scaled down to fit the given bounds while
+    /// keeping its aspect ratio. At least one of the bounds must be given
+    /// 
+    /// parameters:
+    ///     * reference: photo_reference of a Photo found in a place
+    ///     * max_width: Maximum width of the image, from 1 to 1600 pixels
+    ///     * max_height: Maximum height of the image, from 1 to 1600 pixels
+    /// returns: Result<PlacePhoto, GMapsClientError>
+    pub async fn place_photo(
+        &self,
+        reference: &str,
+        max_width: Option<u32>,
+        max_height: Option<u32>,
+    ) -> Result<PlacePhoto, GMapsClientError> {
+
+        let response = self.photo_response(reference, max_width, max_height).await?;
+        let content_type = content_type(&response);
+        let bytes = response.bytes().await.map_err(GMapsClientError::request_failure)?;
+
+        Ok(PlacePhoto {
+            content_type,
+            bytes: bytes.to_vec(),
+        })
+    }
+
+    /// Downloads a photo of a place into the given writer as it is received, without
+    /// holding the whole image in memory. See place_photo for the parameters
+    /// 
+    /// returns: Result<Option<String>, GMapsClientError>, the content type of the photo
+    pub async fn place_photo_to_writer<W>(
+        &self,
+        reference: &str,
+        max_width: Option<u32>,
+        max_height: Option<u32>,
+        writer: &mut W,
+    ) -> Result<Option<String>, GMapsClientError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut response = self.photo_response(reference, max_width, max_height).await?;
+        let content_type = content_type(&response);
+
+        while let Some(chunk) = response.chunk().await.map_err(GMapsClientError::request_failure)? {
+            writer.write_all(&chunk).await?;
+        }
+        writer.flush().await?;
+
+        Ok(content_type)
+    }
+}
+
+fn content_type(response: &reqwest::Response) -> Option<String> {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/photo"))
+            .and(query_param("photo_reference", "AWU5eFj"))
+            .and(query_param("maxwidth", "1600"))
+            .respond_with(
+                ResponseTemplate::new(302)
+                    .insert_header("location", format!("{}/images/AWU5eFj.jpg", server.uri())),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/images/AWU5eFj.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(vec![0xff, 0xd8, 0xff, 0xe0], "image/jpeg"))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    pub async fn test_place_photo_follows_redirect() {
+        let server = server().await;
+        let client = test_client(Some(&server.uri()));
+
+        let photo = client.place_photo("AWU5eFj", Some(4000), None).await.unwrap();
+        assert_eq!(photo.content_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(photo.bytes, vec![0xff, 0xd8, 0xff, 0xe0]);
+    }
+
+    #[tokio::test]
+    pub async fn test_place_photo_to_writer() {
+        let server = server().await;
+        let client = test_client(Some(&server.uri()));
+
+        let mut image = Vec::new();
+        let content_type = client
+            .place_photo_to_writer("AWU5eFj", Some(1600), None, &mut image)
+            .await
+            .unwrap();
+        assert_eq!(content_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(image, vec![0xff, 0xd8, 0xff, 0xe0]);
+    }
+
+    #[tokio::test]
+    pub async fn test_place_photo_requires_a_bound() {
+        let server = MockServer::start().await;
+        let client = test_client(Some(&server.uri()));
+
+        assert!(matches!(
+            client.place_photo("AWU5eFj", None, None).await,
+            Err(GMapsClientError::InvalidRequest)
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+use std::fmt;
+
+use futures::stream::Stream;
+
+use crate::places::{pagination, FindPlaceResponse, Place, PlaceField, TextSearchResponse};
+use crate::types::LatLng;
+use crate::{Api, GMapsClient, GMapsClientError, Validated};
+
+/// Fields requested by a find place request unless others are given
+const DEFAULT_FIND_PLACE_FIELDS: [PlaceField; 4] = [
+    PlaceField::Name,
+    PlaceField::PlaceId,
+    PlaceField::Geometry,
+    PlaceField::FormattedAddress,
+];
+
+/// Area the find place results are biased towards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LocationBias {
+    /// Biases the results towards the location of the caller's ip address
+    IpBias,
+    /// Biases the results towards a single point
+    Point(LatLng),
+    /// Biases the results towards a circle, radius in meters
+    Circle { center: LatLng, radius: u32 },
+    /// Biases the results towards a rectangle given by its south-west and north-east corners
+    Rectangle { sw: LatLng, ne: LatLng },
+}
+
+impl fmt::Display for LocationBias {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LocationBias::IpBias => write!(f, "ipbias"),
+            LocationBias::Point(point) => write!(f, "point:{}", point),
+            LocationBias::Circle { center, radius } => write!(f, "circle:{}@{}", radius, center),
+            LocationBias::Rectangle { sw, ne } => write!(f, "rectangle:{}|{}", sw, ne),
+        }
+    }
+}
+
+/// Builder for a find place request, obtained from GMapsClient::find_place
+#[derive(Debug)]
+pub struct FindPlaceRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    input: String,
+    fields: Vec<PlaceField>,
+    location_bias: Option<LocationBias>,
+    language: Option<String>,
+}
+
+impl<'a> FindPlaceRequest<'a> {
+
+    /// Restricts the response to the given fields, replacing the fields of an earlier
+    /// call. By default name, place_id, geometry and formatted_address
+    pub fn fields(mut self, fields: &[PlaceField]) -> Self {
+        self.fields.clear();
+        for field in fields {
+            if !self.fields.contains(field) {
+                self.fields.push(*field);
+            }
+        }
+        self
+    }
+
+    /// Prefers results in the given area, without a bias google picks one itself
+    pub fn location_bias(mut self, location_bias: LocationBias) -> Self {
+        self.location_bias = Some(location_bias);
+        self
+    }
+
+    /// Overrides the default language of the client
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("input", self.input.clone()),
+            ("inputtype", "textquery".to_string()),
+        ];
+
+        if !self.fields.is_empty() {
+            let fields: Vec<&str> = self.fields.iter().map(|f| f.as_str()).collect();
+            params.push(("fields", fields.join(",")));
+        }
+
+        if let Some(location_bias) = &self.location_bias {
+            params.push(("locationbias", location_bias.to_string()));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the find place api
+    /// 
+    /// returns: Result<FindPlaceResponse, GMapsClientError>
+    pub async fn send(self) -> Result<FindPlaceResponse, GMapsClientError> {
+        self.client
+            .get_api(Api::Places, "maps/api/place/findplacefromtext/json", self.params())
+            .await
+    }
+}
+
+/// Builder for a text search request, obtained from GMapsClient::text_search
+#[derive(Debug)]
+pub struct TextSearchRequest<'a> {
+    client: &'a GMapsClient<Validated>,
+    query: String,
+    location: Option<(LatLng, u32)>,
+    language: Option<String>,
+}
+
+impl<'a> TextSearchRequest<'a> {
+
+    /// Prefers results within radius meters of the location, without it the
+    /// results are not biased towards any area
+    pub fn location(mut self, location: LatLng, radius: u32) -> Self {
+        self.location = Some((location, radius));
+        self
+    }
+
+    /// Overrides the default language of the client
+    pub fn language(mut self, language: &str) -> Self {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![("query", self.query.clone())];
+
+        if let Some((location, radius)) = self.location {
+            params.push(("location", location.to_string()));
+            params.push(("radius", radius.to_string()));
+        }
+
+        if let Some(language) = &self.language {
+            params.push(("language", language.clone()));
+        }
+
+        params
+    }
+
+    /// Sends the request to the text search api
+    /// 
+    /// returns: Result<TextSearchResponse, GMapsClientError>
+    pub async fn send(self) -> Result<TextSearchResponse, GMapsClientError> {
+        self.client
+            .get_api(Api::Places, "maps/api/place/textsearch/json", self.params())
+            .await
+    }
+
+    /// Streams the places found by the request, following the result pages until
+    /// max_results places were yielded or the results are exhausted
+    /// 
+    /// returns: Stream<Item = Result<Place, GMapsClientError>>
+    pub fn stream(self, max_results: usize) -> impl Stream<Item = Result<Place, GMapsClientError>> + 'a {
+        pagination::paginate(self.client, "maps/api/place/textsearch/json", self.params(), max_results)
+    }
+}
+
+impl GMapsClient<Validated> {
+
+    /// Starts a request finding a single place given as text
+    /// 
+    /// parameters:
+    ///     * input: Description of the desired place in natural language
+    /// returns: FindPlaceRequest, sent with FindPlaceRequest::send
+    pub fn find_place(&self, input: &str) -> FindPlaceRequest<'_> {
+        FindPlaceRequest {
+            client: self,
+            input: input.to_string(),
+            fields: DEFAULT_FIND_PLACE_FIELDS.to_vec(),
+            location_bias: None,
+            language: None,
+        }
+    }
+
+    /// Starts a request searching for places matching a natural language query
+    /// 
+    /// parameters:
+    ///     * query: Description of the desired places in natural language
+    /// returns: TextSearchRequest, sent with TextSearchRequest::send
+    pub fn text_search(&self, query: &str) -> TextSearchRequest<'_> {
+        TextSearchRequest {
+            client: self,
+            query: query.to_string(),
+            location: None,
+            language: None,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_client;
+    use wiremock::matchers::{method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    pub fn test_location_bias_format() {
+        let center = LatLng::new(46.07, 23.58);
+
+        assert_eq!(LocationBias::IpBias.to_string(), "ipbias");
+        assert_eq!(LocationBias::Point(center).to_string(), "point:46.07,23.58");
+        assert_eq!(
+            LocationBias::Circle { center, radius: 2000 }.to_string(),
+            "circle:2000@46.07,23.58"
+        );
+        assert_eq!(
+            LocationBias::Rectangle { sw: LatLng::new(46.0, 23.5), ne: center }.to_string(),
+            "rectangle:46,23.5|46.07,23.58"
+        );
+    }
+
+    #[test]
+    pub fn test_find_place_params() {
+        let client = test_client(None);
+
+        assert_eq!(
+            client.find_place("bosfor alba").params(),
+            vec![
+                ("input", "bosfor alba".to_string()),
+                ("inputtype", "textquery".to_string()),
+                ("fields", "name,place_id,geometry,formatted_address".to_string()),
+            ]
+        );
+
+        let request = client
+            .find_place("bosfor alba")
+            .fields(&[PlaceField::PlaceId])
+            .location_bias(LocationBias::IpBias)
+            .language("ro");
+        assert_eq!(
+            request.params(),
+            vec![
+                ("input", "bosfor alba".to_string()),
+                ("inputtype", "textquery".to_string()),
+                ("fields", "place_id".to_string()),
+                ("locationbias", "ipbias".to_string()),
+                ("language", "ro".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_text_search_params() {
+        let client = test_client(None);
+
+        assert_eq!(
+            client.text_search("pizza").params(),
+            vec![("query", "pizza".to_string())]
+        );
+
+        let request = client
+            .text_search("pizza")
+            .location(LatLng::new(46.07, 23.58), 5000);
+        assert_eq!(
+            request.params(),
+            vec![
+                ("query", "pizza".to_string()),
+                ("location", "46.07,23.58".to_string()),
+                ("radius", "5000".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    pub async fn test_search_input_is_encoded() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/findplacefromtext/json"))
+            .and(query_param("input", "Piața Unirii & #1+2"))
+            .and(query_param("key", "test_key"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{ "candidates": [{ "name": "Piața Unirii" }], "status": "OK" }"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/place/textsearch/json"))
+            .and(query_param("query", "pizza & pasta in Târgu Mureș"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                r#"{ "results": [{ "name": "Pizza Party" }], "status": "OK" }"#,
+            ))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = test_client(Some(&server.uri()));
+
+        let response = client.find_single_place_from_text("Piața Unirii & #1+2").await.unwrap();
+        assert_eq!(response.candidates[0].name.as_deref(), Some("Piața Unirii"));
+
+        let response = client.find_places_from_text("pizza & pasta in Târgu Mureș").await.unwrap();
+        assert_eq!(response.results[0].name.as_deref(), Some("Pizza Party"));
+    }
+}
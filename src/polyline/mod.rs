@@ -0,0 +1,190 @@
+//! Encoding and decoding of google's encoded polyline format
+//! https://developers.google.com/maps/documentation/utilities/polylinealgorithm
+
+use crate::types::LatLng;
+use crate::{GMapsClientError, DECODE_SNIPPET_LEN};
+
+/// Precision used by the directions api polylines
+pub const DEFAULT_PRECISION: u32 = 5;
+
+/// Offset added to every 5 bit chunk so that it maps to a printable character
+const CHAR_OFFSET: u8 = 63;
+
+/// Encodes a path into a polyline string
+/// 
+/// parameters:
+///     * path: Points of the path, in order
+///     * precision: Number of decimals kept, 5 for most google apis, 6 for some others
+/// returns: Result<String, GMapsClientError> failing with InvalidCoordinates for points
+/// outside of the valid latitudes and longitudes or too precise to be encoded
+pub fn encode(path: &[LatLng], precision: u32) -> Result<String, GMapsClientError> {
+    let factor = 10_f64.powi(precision as i32);
+    let mut encoded = String::new();
+    let (mut previous_lat, mut previous_lng) = (0_i64, 0_i64);
+
+    for point in path {
+        let invalid = || GMapsClientError::InvalidCoordinates(*point);
+        if !(-90.0..=90.0).contains(&point.lat) || !(-180.0..=180.0).contains(&point.lng) {
+            return Err(invalid());
+        }
+
+        let lat = (point.lat * factor).round() as i64;
+        let lng = (point.lng * factor).round() as i64;
+
+        encode_value(lat.checked_sub(previous_lat).ok_or_else(invalid)?, &mut encoded);
+        encode_value(lng.checked_sub(previous_lng).ok_or_else(invalid)?, &mut encoded);
+
+        previous_lat = lat;
+        previous_lng = lng;
+    }
+
+    Ok(encoded)
+}
+
+/// Decodes a polyline string into the path it describes
+/// 
+/// parameters:
+///     * polyline: Encoded polyline, as found in route and step responses
+///     * precision: Number of decimals the polyline was encoded with
+/// returns: Result<Vec<LatLng>, GMapsClientError>
+pub fn decode(polyline: &str, precision: u32) -> Result<Vec<LatLng>, GMapsClientError> {
+    let factor = 10_f64.powi(precision as i32);
+    let mut bytes = polyline.bytes();
+    let mut path = Vec::new();
+    let (mut lat, mut lng) = (0_i64, 0_i64);
+
+    let overflow = || invalid_polyline(polyline);
+
+    while bytes.len() > 0 {
+        lat = lat.checked_add(decode_value(&mut bytes, polyline)?).ok_or_else(overflow)?;
+        lng = lng.checked_add(decode_value(&mut bytes, polyline)?).ok_or_else(overflow)?;
+
+        path.push(LatLng::new(lat as f64 / factor, lng as f64 / factor));
+    }
+
+    Ok(path)
+}
+
+/// Error for a malformed polyline, keeping only its beginning since overview
+/// polylines run to several kilobytes
+fn invalid_polyline(polyline: &str) -> GMapsClientError {
+    GMapsClientError::InvalidPolyline(polyline.chars().take(DECODE_SNIPPET_LEN).collect())
+}
+
+fn encode_value(value: i64, encoded: &mut String) {
+    let mut value = ((value << 1) ^ (value >> 63)) as u64;
+
+    while value >= 0x20 {
+        encoded.push((((value & 0x1f) as u8 | 0x20) + CHAR_OFFSET) as char);
+        value >>= 5;
+    }
+    encoded.push((value as u8 + CHAR_OFFSET) as char);
+}
+
+fn decode_value(
+    bytes: &mut std::str::Bytes<'_>,
+    polyline: &str,
+) -> Result<i64, GMapsClientError> {
+    let invalid = || invalid_polyline(polyline);
+    let mut result = 0_u64;
+    let mut shift = 0;
+
+    loop {
+        let byte = bytes.next().ok_or_else(invalid)?;
+        if !(CHAR_OFFSET..=CHAR_OFFSET + 0x3f).contains(&byte) || shift > 60 {
+            return Err(invalid());
+        }
+
+        let chunk = (byte - CHAR_OFFSET) as u64;
+        result |= (chunk & 0x1f) << shift;
+        shift += 5;
+
+        if chunk & 0x20 == 0 {
+            break;
+        }
+    }
+
+    let value = (result >> 1) as i64;
+    Ok(if result & 1 == 1 { !value } else { value })
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use proptest::prelude::*;
+
+    fn documented_path() -> Vec<LatLng> {
+        vec![
+            LatLng::new(38.5, -120.2),
+            LatLng::new(40.7, -120.95),
+            LatLng::new(43.252, -126.453),
+        ]
+    }
+
+    #[test]
+    pub fn test_encode_documented_example() {
+        assert_eq!(encode(&documented_path(), 5).unwrap(), "_p~iF~ps|U_ulLnnqC_mqNvxq`@");
+    }
+
+    #[test]
+    pub fn test_encode_rejects_invalid_points() {
+        let infinite = [LatLng::new(f64::INFINITY, 0.0), LatLng::new(f64::NEG_INFINITY, 0.0)];
+        assert!(matches!(encode(&infinite, 5), Err(GMapsClientError::InvalidCoordinates(_))));
+        assert!(encode(&[LatLng::new(f64::NAN, 0.0)], 5).is_err());
+        assert!(encode(&[LatLng::new(0.0, 180.5)], 5).is_err());
+
+        // deltas past the range of i64 once scaled
+        let path = [LatLng::new(90.0, 0.0), LatLng::new(-90.0, 0.0)];
+        assert!(encode(&path, 18).is_err());
+    }
+
+    #[test]
+    pub fn test_decode_documented_example() {
+        assert_eq!(decode("_p~iF~ps|U_ulLnnqC_mqNvxq`@", 5).unwrap(), documented_path());
+    }
+
+    #[test]
+    pub fn test_decode_rejects_malformed_polylines() {
+        // truncated in the middle of a value
+        assert!(decode("_p~iF~ps|", 5).is_err());
+        // missing the longitude of the last point
+        assert!(decode("_p~iF", 5).is_err());
+        // character outside of the encoding alphabet
+        assert!(decode("_p~iF ps|U", 5).is_err());
+
+        // deltas adding up past the range of the coordinates
+        let mut overflowing = String::new();
+        for value in [i64::MAX, 0, i64::MAX, 0] {
+            encode_value(value, &mut overflowing);
+        }
+        assert!(matches!(decode(&overflowing, 5), Err(GMapsClientError::InvalidPolyline(_))));
+
+        // only the beginning of a long polyline is kept in the error
+        let long = format!("{}_p~iF", "_p~iF~ps|U".repeat(1000));
+        match decode(&long, 5) {
+            Err(GMapsClientError::InvalidPolyline(snippet)) => assert_eq!(snippet.len(), DECODE_SNIPPET_LEN),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    fn path(precision: u32) -> impl Strategy<Value = Vec<LatLng>> {
+        let factor = 10_i64.pow(precision);
+        let point = (-90 * factor..=90 * factor, -180 * factor..=180 * factor).prop_map(
+            move |(lat, lng)| LatLng::new(lat as f64 / factor as f64, lng as f64 / factor as f64),
+        );
+        prop::collection::vec(point, 0..50)
+    }
+
+    proptest! {
+        #[test]
+        fn test_round_trip_precision_5(path in path(5)) {
+            prop_assert_eq!(decode(&encode(&path, 5).unwrap(), 5).unwrap(), path);
+        }
+
+        #[test]
+        fn test_round_trip_precision_6(path in path(6)) {
+            prop_assert_eq!(decode(&encode(&path, 6).unwrap(), 6).unwrap(), path);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use reqwest::Url;
+
+use crate::auth::REDACTED;
+
+/// Query parameters carrying credentials, redacted from the urls kept in errors
+const SECRET_PARAMS: [&str; 2] = ["key", "signature"];
+
+/// Query parameters of a request, percent-encoded when turned into a url
+/// 
+/// Values are encoded as application/x-www-form-urlencoded, so user input containing
+/// reserved characters such as &, # or + and non-ascii text reaches the api unchanged
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct QueryParams {
+    params: Vec<(&'static str, String)>,
+}
+
+impl QueryParams {
+
+    pub(crate) fn new() -> QueryParams {
+        QueryParams::default()
+    }
+
+    /// Appends a parameter, keeping any previous one of the same name
+    pub(crate) fn push(&mut self, name: &'static str, value: impl ToString) {
+        self.params.push((name, value.to_string()));
+    }
+
+    /// Appends a parameter unless one of the same name is already present
+    pub(crate) fn push_default(&mut self, name: &'static str, value: impl ToString) {
+        if !self.contains(name) {
+            self.push(name, value);
+        }
+    }
+
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.params.iter().any(|(param, _)| *param == name)
+    }
+
+    /// Builds the url of the given base followed by the encoded parameters. The base
+    /// urls are validated when the client is built
+    pub(crate) fn to_url(&self, base: &str) -> Url {
+        let mut url = Url::parse(base).expect("the api urls are always valid");
+        if !self.params.is_empty() {
+            url.query_pairs_mut()
+                .extend_pairs(self.params.iter().map(|(name, value)| (*name, value.as_str())));
+        }
+        url
+    }
+}
+
+impl From<Vec<(&'static str, String)>> for QueryParams {
+    fn from(params: Vec<(&'static str, String)>) -> QueryParams {
+        QueryParams { params }
+    }
+}
+
+/// Replaces the values of the credential parameters of the url, so that it can be
+/// shown in errors and logs
+pub(crate) fn redact_url(url: &mut Url) {
+    if !url.query_pairs().any(|(name, _)| SECRET_PARAMS.contains(&name.as_ref())) {
+        return;
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(name, value)| {
+            let value = match SECRET_PARAMS.contains(&name.as_ref()) {
+                true => REDACTED.to_string(),
+                false => value.into_owned(),
+            };
+            (name.into_owned(), value)
+        })
+        .collect();
+
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+
+    fn encode(value: &str) -> String {
+        let mut params = QueryParams::new();
+        params.push("query", value);
+        params.to_url("https://maps.googleapis.com/maps/api/place/textsearch/json")
+            .query()
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    pub fn test_reserved_characters_are_encoded() {
+        assert_eq!(encode("pizza & pasta"), "query=pizza+%26+pasta");
+        assert_eq!(encode("room #4"), "query=room+%234");
+        assert_eq!(encode("c++"), "query=c%2B%2B");
+        assert_eq!(encode("a=b?c/d"), "query=a%3Db%3Fc%2Fd");
+    }
+
+    #[test]
+    pub fn test_unicode_is_encoded() {
+        assert_eq!(encode("Piața Unirii"), "query=Pia%C8%9Ba+Unirii");
+        assert_eq!(encode("Târgu Mureș"), "query=T%C3%A2rgu+Mure%C8%99");
+    }
+
+    #[test]
+    pub fn test_encoded_values_round_trip() {
+        let mut params = QueryParams::new();
+        params.push("input", "Piața Unirii & #1+2");
+        params.push("radius", 5000);
+        let url = params.to_url("https://maps.googleapis.com/maps/api/place/findplacefromtext/json");
+
+        let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(
+            pairs,
+            vec![
+                ("input".to_string(), "Piața Unirii & #1+2".to_string()),
+                ("radius".to_string(), "5000".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    pub fn test_push_default_keeps_existing() {
+        let mut params = QueryParams::new();
+        params.push("language", "ro");
+        params.push_default("language", "en");
+        params.push_default("region", "ro");
+
+        assert_eq!(
+            params,
+            QueryParams::from(vec![
+                ("language", "ro".to_string()),
+                ("region", "ro".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    pub fn test_redact_url() {
+        let mut url = Url::parse("https://maps.googleapis.com/maps/api/geocode/json?address=Alba+Iulia&client=gme-test&key=secret_key&signature=abc%3D").unwrap();
+        redact_url(&mut url);
+
+        assert_eq!(
+            url.as_str(),
+            "https://maps.googleapis.com/maps/api/geocode/json?address=Alba+Iulia&client=gme-test&key=%5BREDACTED%5D&signature=%5BREDACTED%5D"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use tokio::time::Instant;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::{Api, GMapsClient, GMapsClientError};
+
+/// Quota of requests a client may send to an api
+/// 
+/// Requests are admitted by a token bucket holding up to burst tokens and refilled at
+/// requests_per_second. Requests exceeding the quota wait for their turn, unless fail
+/// fast is enabled in which case they fail with GMapsClientError::RateLimited
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    requests_per_second: f64,
+    burst: u32,
+    fail_fast: bool,
+}
+
+impl RateLimit {
+
+    /// Allows the given number of requests per second, with a burst of the same size
+    /// 
+    /// returns: Result<RateLimit, GMapsClientError> failing with InvalidRateLimit unless
+    /// the rate is finite, above zero and the time between two requests fits in a Duration
+    pub fn per_second(requests_per_second: f64) -> Result<RateLimit, GMapsClientError> {
+
+        if !requests_per_second.is_finite()
+            || requests_per_second <= 0.0
+            || Duration::try_from_secs_f64(1.0 / requests_per_second).is_err()
+        {
+            return Err(GMapsClientError::InvalidRateLimit(requests_per_second));
+        }
+
+        Ok(RateLimit {
+            requests_per_second,
+            burst: (requests_per_second.ceil() as u32).max(1),
+            fail_fast: false,
+        })
+    }
+
+    /// Maximum number of requests sent at once after a quiet period
+    pub fn burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Fails the requests exceeding the quota instead of waiting
+    pub fn fail_fast(mut self, fail_fast: bool) -> Self {
+        self.fail_fast = fail_fast;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    /// Can go below zero, each missing token being a request waiting for its turn
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Token bucket enforcing a RateLimit, shared by every clone of a client
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    limit: RateLimit,
+    bucket: Mutex<Bucket>,
+}
+
+impl RateLimiter {
+
+    pub(crate) fn new(limit: RateLimit) -> RateLimiter {
+        RateLimiter {
+            limit,
+            bucket: Mutex::new(Bucket {
+                tokens: limit.burst as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Takes a token, returning how long the caller has to wait before sending its request
+    fn reserve(&self, api: Api) -> Result<Duration, GMapsClientError> {
+        let mut bucket = self.bucket.lock().expect("rate limiter lock poisoned");
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.limit.requests_per_second)
+            .min(self.limit.burst as f64);
+        bucket.last_refill = now;
+
+        if bucket.tokens < 1.0 && self.limit.fail_fast {
+            return Err(GMapsClientError::RateLimited { api });
+        }
+
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            return Ok(Duration::ZERO);
+        }
+
+        // a long queue behind a very low rate can wait longer than a Duration holds
+        Ok(Duration::try_from_secs_f64(-bucket.tokens / self.limit.requests_per_second)
+            .unwrap_or(Duration::MAX))
+    }
+
+    /// Waits until a request can be sent without exceeding the quota
+    pub(crate) async fn acquire(&self, api: Api) -> Result<(), GMapsClientError> {
+        let wait = self.reserve(api)?;
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+}
+
+impl<T> GMapsClient<T> {
+
+    /// Waits for the rate limiter of the given api, if one is configured
+    pub(crate) async fn acquire(&self, api: Api) -> Result<(), GMapsClientError> {
+        match self.rate_limiters.get(&api) {
+            Some(rate_limiter) => rate_limiter.acquire(api).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_builder;
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_requests_wait_for_their_turn() {
+        let rate_limiter = RateLimiter::new(RateLimit::per_second(2.0).unwrap().burst(2));
+        let start = Instant::now();
+
+        for _ in 0..2 {
+            rate_limiter.acquire(Api::Places).await.unwrap();
+        }
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        rate_limiter.acquire(Api::Places).await.unwrap();
+        rate_limiter.acquire(Api::Places).await.unwrap();
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[test]
+    pub fn test_invalid_rates_are_rejected() {
+        for rate in [0.0, -1.0, 1e-20, f64::NAN, f64::INFINITY] {
+            assert!(matches!(RateLimit::per_second(rate), Err(GMapsClientError::InvalidRateLimit(_))));
+        }
+        assert!(RateLimit::per_second(0.5).is_ok());
+    }
+
+    #[test]
+    pub fn test_waits_past_duration_max_are_capped() {
+        let rate_limiter = RateLimiter::new(RateLimit::per_second(1e-19).unwrap());
+
+        for _ in 0..3 {
+            assert!(rate_limiter.reserve(Api::Directions).is_ok());
+        }
+        assert_eq!(rate_limiter.reserve(Api::Directions).unwrap(), Duration::MAX);
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_fail_fast() {
+        let rate_limiter = RateLimiter::new(RateLimit::per_second(1.0).unwrap().fail_fast(true));
+
+        rate_limiter.acquire(Api::Directions).await.unwrap();
+        assert!(matches!(
+            rate_limiter.acquire(Api::Directions).await,
+            Err(GMapsClientError::RateLimited { api: Api::Directions })
+        ));
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(rate_limiter.acquire(Api::Directions).await.is_ok());
+    }
+
+    #[tokio::test(start_paused = true)]
+    pub async fn test_limit_is_shared_across_clones() {
+        let client = test_builder(None)
+            .rate_limit(Api::Geocoding, RateLimit::per_second(1.0).unwrap().fail_fast(true))
+            .build()
+            .unwrap();
+        let clone = client.clone();
+
+        assert!(client.acquire(Api::Geocoding).await.is_ok());
+        assert!(clone.acquire(Api::Geocoding).await.is_err());
+        assert!(clone.acquire(Api::Places).await.is_ok());
+    }
+}
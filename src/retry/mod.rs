@@ -0,0 +1,278 @@
+use std::future::Future;
+use std::time::Duration;
+
+use crate::{random_u64, GMapsClient, GMapsClientError};
+
+/// Failures that can be retried by a RetryPolicy
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryCondition {
+    /// The api answered with the UNKNOWN_ERROR status
+    UnknownError,
+    /// The api answered with the OVER_QUERY_LIMIT status
+    OverQueryLimit,
+    /// The server answered with a 5xx http status
+    ServerError,
+    /// The request could not be sent or timed out before an answer was received
+    Transport,
+}
+
+impl RetryCondition {
+    fn matches(&self, error: &GMapsClientError) -> bool {
+        match (self, error) {
+            (RetryCondition::UnknownError, GMapsClientError::UnknownError) => true,
+            (RetryCondition::OverQueryLimit, GMapsClientError::OverQueryLimit) => true,
+            (RetryCondition::ServerError, GMapsClientError::RequestFailure(error)) => error
+                .status()
+                .is_some_and(|status| status.is_server_error()),
+            (RetryCondition::Transport, GMapsClientError::RequestFailure(error)) => {
+                error.status().is_none()
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Describes how failed requests are retried, applied to every endpoint of the client
+/// 
+/// The delay before the nth retry is base_delay * 2^(n-1), capped at max_delay. With
+/// jitter enabled a random delay between half and the whole of that value is used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retry_on: Vec<RetryCondition>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            jitter: true,
+            retry_on: vec![
+                RetryCondition::UnknownError,
+                RetryCondition::OverQueryLimit,
+                RetryCondition::ServerError,
+                RetryCondition::Transport,
+            ],
+        }
+    }
+}
+
+impl RetryPolicy {
+
+    pub fn new() -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Policy sending every request exactly once
+    pub fn none() -> RetryPolicy {
+        RetryPolicy::default().max_attempts(1)
+    }
+
+    /// Total number of attempts, the first one included
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry, doubled for every following one
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Upper bound of the delay between two attempts
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Randomizes the delays so that clients failing together do not retry together
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Replaces the failures that are retried
+    pub fn retry_on(mut self, retry_on: &[RetryCondition]) -> Self {
+        self.retry_on = retry_on.to_vec();
+        self
+    }
+
+    fn should_retry(&self, error: &GMapsClientError) -> bool {
+        self.retry_on.iter().any(|condition| condition.matches(error))
+    }
+
+    /// Delay to wait after the given failed attempt, starting from 1
+    fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self
+            .base_delay
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay);
+
+        if !self.jitter {
+            return delay;
+        }
+
+        let half = delay / 2;
+        half + Duration::from_nanos(random_u64() % (half.as_nanos() as u64 + 1))
+    }
+}
+
+impl<T> GMapsClient<T> {
+
+    /// Runs the given request until it succeeds, fails with an error the retry
+    /// policy does not cover, or runs out of attempts
+    /// 
+    /// returns: Result<R, GMapsClientError>
+    pub(crate) async fn with_retry<R, F, Fut>(&self, mut send: F) -> Result<R, GMapsClientError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<R, GMapsClientError>>,
+    {
+        let policy = &self.retry_policy;
+        let mut attempt = 1;
+
+        loop {
+            match send().await {
+                Err(error) if policy.should_retry(&error) => {
+                    if attempt >= policy.max_attempts {
+                        if attempt == 1 {
+                            return Err(error);
+                        }
+                        return Err(GMapsClientError::RetriesExhausted {
+                            attempts: attempt,
+                            source: Box::new(error),
+                        });
+                    }
+
+                    tokio::time::sleep(policy.delay(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+
+    use super::*;
+    use crate::test_builder;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    pub fn test_delay_backoff() {
+        let policy = RetryPolicy::new()
+            .base_delay(Duration::from_millis(100))
+            .max_delay(Duration::from_millis(350))
+            .jitter(false);
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(350));
+        assert_eq!(policy.delay(40), Duration::from_millis(350));
+    }
+
+    #[test]
+    pub fn test_delay_jitter_bounds() {
+        let policy = RetryPolicy::new().base_delay(Duration::from_millis(100));
+
+        for _ in 0..100 {
+            let delay = policy.delay(2);
+            assert!(delay >= Duration::from_millis(100));
+            assert!(delay <= Duration::from_millis(200));
+        }
+    }
+
+    async fn client(server: &MockServer, policy: RetryPolicy) -> GMapsClient<crate::Validated> {
+        test_builder(Some(&server.uri()))
+            .retry_policy(policy.base_delay(Duration::from_millis(1)))
+            .build()
+            .unwrap()
+            .assume_validated()
+    }
+
+    #[tokio::test]
+    pub async fn test_retries_until_success() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/geocode/json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "results": [], "status": "UNKNOWN_ERROR" }"#),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/geocode/json"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/maps/api/geocode/json"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "results": [], "status": "OK" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, RetryPolicy::new()).await;
+        assert!(client.geocode("Alba Iulia").send().await.is_ok());
+    }
+
+    #[tokio::test]
+    pub async fn test_retries_exhausted() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "results": [], "status": "OVER_QUERY_LIMIT" }"#),
+            )
+            .expect(4)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, RetryPolicy::new().max_attempts(4)).await;
+        match client.geocode("Alba Iulia").send().await {
+            Err(GMapsClientError::RetriesExhausted { attempts, source }) => {
+                assert_eq!(attempts, 4);
+                assert!(matches!(*source, GMapsClientError::OverQueryLimit));
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    pub async fn test_non_retryable_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_string(r#"{ "results": [], "status": "OVER_QUERY_LIMIT" }"#),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let policy = RetryPolicy::new().retry_on(&[RetryCondition::UnknownError]);
+        let client = client(&server, policy).await;
+        assert!(matches!(
+            client.geocode("Alba Iulia").send().await,
+            Err(GMapsClientError::OverQueryLimit)
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use std::fmt;
+
+/// Geographic coordinates as returned and accepted by the google maps apis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatLng {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl LatLng {
+    pub fn new(lat: f64, lng: f64) -> LatLng {
+        LatLng { lat, lng }
+    }
+}
+
+/// Formats the coordinates as "lat,lng", the form expected in request urls
+impl fmt::Display for LatLng {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.lat, self.lng)
+    }
+}
+
+/// Recommended viewport for displaying a result
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Viewport {
+    pub northeast: LatLng,
+    pub southwest: LatLng,
+}
+
+/// Location of a result and the viewport it should be displayed in
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Geometry {
+    pub location: LatLng,
+
+    #[serde(default)]
+    pub viewport: Option<Viewport>,
+
+    /// Fields not modeled by this crate
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Rectangle delimiting an area, such as the extent of a route
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bounds {
+    pub northeast: LatLng,
+    pub southwest: LatLng,
+}
+
+/// Distance in meters along with its human readable form
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Distance {
+    pub text: String,
+    pub value: u64,
+}
+
+/// Travel duration in seconds along with its human readable form, named apart from
+/// std::time::Duration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TravelDuration {
+    pub text: String,
+    pub value: u64,
+}